@@ -1,4 +1,5 @@
-use crate::MissionStats;
+use crate::{MissionStats, SaRisk};
+use tracing::debug;
 
 /// Function to calculate 'less than' or 'equal to' between two MissionStats objects
 /// to determine SA rating
@@ -12,3 +13,166 @@ pub fn sa_compare(stats: MissionStats, sa_combination: MissionStats) -> bool {
         && stats.innocents_killed <= sa_combination.innocents_killed
         && stats.innocents_harmed <= sa_combination.innocents_harmed
 }
+
+/// Single actions of the player as stat increments, in the order of `MissionStats::to_array`.
+/// A headshot is always fired as a shot.
+const ACTIONS: [[u32; 8]; 8] = [
+    [1, 0, 0, 0, 0, 0, 0, 0],
+    [1, 0, 1, 0, 0, 0, 0, 0],
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 1, 0, 0, 0, 0],
+    [0, 0, 0, 0, 1, 0, 0, 0],
+    [0, 0, 0, 0, 0, 1, 0, 0],
+    [0, 0, 0, 0, 0, 0, 1, 0],
+    [0, 0, 0, 0, 0, 0, 0, 1],
+];
+
+/// Function to determine the graded SA status from the valid SA combinations of a game.
+/// SA is at risk if a single action would lose the rating. Actions that lose it even in a
+/// clean mission, such as hurting an innocent in Contracts, are never allowed and are not
+/// counted as a risk.
+pub fn sa_risk(stats: MissionStats, sa_combinations: &[MissionStats]) -> SaRisk {
+    let is_sa = |stats: MissionStats| {
+        sa_combinations
+            .iter()
+            .any(|combination| sa_compare(stats, *combination))
+    };
+    let after = |stats: MissionStats, action: &[u32; 8]| {
+        let mut values = stats.to_array();
+        for (value, increment) in values.iter_mut().zip(action) {
+            *value = value.saturating_add(*increment);
+        }
+        MissionStats::from_array(values)
+    };
+
+    if !is_sa(stats) {
+        return SaRisk::Lost;
+    }
+
+    let at_risk = ACTIONS
+        .iter()
+        .filter(|action| is_sa(after(MissionStats::default(), action)))
+        .any(|action| !is_sa(after(stats, action)));
+
+    if at_risk {
+        SaRisk::AtRisk
+    } else {
+        SaRisk::Safe
+    }
+}
+
+/// Log a change of the map code read from the game, to follow the game in the log file.
+/// Unknown codes are read in the menus and while loading.
+pub fn log_map_change(last_map_code: &mut String, map_code: &str, mission_name: Option<&str>) {
+    if last_map_code != map_code {
+        debug!(map_code, mission = mission_name, "Map changed");
+        *last_map_code = map_code.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::backends::{hm2, hmc};
+
+    /// Deterministic xorshift generator for the property tests
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, bound: u32) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as u32
+        }
+
+        /// Random stats with mostly small values, and occasionally many shots
+        fn stats(&mut self) -> MissionStats {
+            let mut stats = [0; 8].map(|_| self.below(4));
+            if self.below(4) == 0 {
+                stats[0] = self.below(5000);
+            }
+            stats[2] = stats[2].min(stats[0]);
+            MissionStats::from_array(stats)
+        }
+    }
+
+    fn risk_level(risk: SaRisk) -> u8 {
+        match risk {
+            SaRisk::Safe => 0,
+            SaRisk::AtRisk => 1,
+            SaRisk::Lost => 2,
+        }
+    }
+
+    #[test]
+    fn combination_tables_are_minimal() {
+        // An entry within the limits of another entry never changes the rating
+        for table in [&hm2::SA_COMBINATIONS[..], &hmc::SA_COMBINATIONS[..]] {
+            for (i, a) in table.iter().enumerate() {
+                for (j, b) in table.iter().enumerate() {
+                    assert!(
+                        i == j || !sa_compare(*a, *b),
+                        "{:?} is covered by {:?}",
+                        a.to_array(),
+                        b.to_array()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sa_compare_is_monotone() {
+        let mut rng = Rng(0x5EED);
+        let combinations: Vec<MissionStats> = hm2::SA_COMBINATIONS
+            .iter()
+            .chain(hmc::SA_COMBINATIONS.iter())
+            .copied()
+            .collect();
+
+        for _ in 0..10_000 {
+            let stats = rng.stats();
+            let mut more = stats.to_array();
+            more[rng.below(8) as usize] += rng.below(3);
+            let more = MissionStats::from_array(more);
+
+            // Fewer stats than a valid combination are always valid as well
+            for combination in &combinations {
+                assert!(sa_compare(*combination, *combination));
+                if sa_compare(more, *combination) {
+                    assert!(sa_compare(stats, *combination));
+                }
+            }
+
+            // The rating can only get worse as stats increase
+            for table in [&hm2::SA_COMBINATIONS[..], &hmc::SA_COMBINATIONS[..]] {
+                assert!(risk_level(sa_risk(stats, table)) <= risk_level(sa_risk(more, table)));
+            }
+        }
+    }
+
+    #[test]
+    fn sa_risk_of_known_stats() {
+        // Hurting an innocent in Contracts always loses SA, which is not a risk of the mission
+        let clean = MissionStats::default();
+        assert_eq!(sa_risk(clean, &hm2::SA_COMBINATIONS), SaRisk::Safe);
+        assert_eq!(sa_risk(clean, &hmc::SA_COMBINATIONS), SaRisk::Safe);
+
+        // Hitman 2 allows killing a single innocent
+        let innocent = MissionStats::new(0, 0, 0, 0, 0, 0, 1, 0);
+        assert_eq!(sa_risk(innocent, &hm2::SA_COMBINATIONS), SaRisk::AtRisk);
+        assert_eq!(sa_risk(innocent, &hmc::SA_COMBINATIONS), SaRisk::Lost);
+
+        // Missed shots only count in Contracts once someone is hurt
+        let missed = MissionStats::new(500, 0, 0, 1, 0, 0, 0, 0);
+        assert_eq!(sa_risk(missed, &hm2::SA_COMBINATIONS), SaRisk::Lost);
+        assert_eq!(sa_risk(missed, &hmc::SA_COMBINATIONS), SaRisk::AtRisk);
+
+        // Stats at their maximum value do not overflow
+        let maxed = MissionStats::new(u32::MAX, 0, 0, 0, 0, 0, 0, 0);
+        assert_eq!(sa_risk(maxed, &hmc::SA_COMBINATIONS), SaRisk::Lost);
+        let maxed = MissionStats::from_array([u32::MAX; 8]);
+        assert_eq!(sa_risk(maxed, &hm2::SA_COMBINATIONS), SaRisk::Lost);
+    }
+}
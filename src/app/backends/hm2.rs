@@ -1,7 +1,19 @@
-use super::backend_helpers::sa_compare;
+use super::backend_helpers::{log_map_change, sa_risk};
+use super::missions::{Mission, MissionTable};
 use crate::app::{memory::*, system_access::get_process_window};
-use crate::{Backend, GameData, MissionStats, Rating, Window};
-use std::collections::HashMap;
+use crate::{Backend, Difficulty, GameData, MissionStats, Rating, Window};
+use std::sync::OnceLock;
+use tracing::{debug, info};
+
+/// Full title of the game, as used by split files
+pub const GAME_NAME: &str = "Hitman 2: Silent Assassin";
+
+/// Difficulty levels offered by the game, from easiest to hardest
+pub const DIFFICULTIES: [Difficulty; 3] = [
+    Difficulty::Normal,
+    Difficulty::Expert,
+    Difficulty::Professional,
+];
 
 /// Memory addresses
 const BASE_ADDRESS: usize = 0x400000;
@@ -22,13 +34,14 @@ const ENEMIES_HARMED: [usize; 3] = [0x28, 0, 0x20C];
 const INNOCENTS_KILLED: [usize; 3] = [0x28, 0, 0x218];
 const INNOCENTS_HARMED: [usize; 3] = [0x28, 0, 0x214];
 
-const MAP_OFFSETS: [usize; 20] = [
+/// Stat offsets of the missions with ratings
+pub const MAP_OFFSETS: [usize; 20] = [
     0x838, 0xB24, 0x8A0, 0x138, 0xB88, 0xBB8, 0xB48, 0xCE8, 0x136C, 0xAD0, 0xF50, 0x8D4, 0x9EC,
     0x400, 0x9EC, 0x644, 0xB08, 0x96C, 0xB00, 0x8,
 ];
 
 /// Valid silent assassin combinations
-pub const SA_COMBINATIONS: [MissionStats; 27] = [
+pub const SA_COMBINATIONS: [MissionStats; 25] = [
     MissionStats::new(0, 1, 0, 0, 1, 2, 0, 0),
     MissionStats::new(0, 1, 0, 0, 0, 5, 0, 0),
     MissionStats::new(0, 1, 0, 0, 0, 2, 0, 1),
@@ -39,7 +52,6 @@ pub const SA_COMBINATIONS: [MissionStats; 27] = [
     MissionStats::new(0, 0, 0, 1, 0, 3, 0, 1),
     MissionStats::new(0, 0, 0, 1, 0, 0, 1, 0),
     MissionStats::new(0, 0, 0, 1, 0, 0, 0, 2),
-    MissionStats::new(0, 0, 0, 0, 1, 0, 0, 1),
     MissionStats::new(1, 1, 1, 0, 0, 2, 0, 0),
     MissionStats::new(1, 1, 0, 0, 1, 0, 0, 0),
     MissionStats::new(1, 1, 0, 0, 0, 3, 0, 0),
@@ -50,7 +62,6 @@ pub const SA_COMBINATIONS: [MissionStats; 27] = [
     MissionStats::new(1, 0, 0, 1, 1, 1, 0, 0),
     MissionStats::new(1, 0, 0, 1, 0, 4, 0, 0),
     MissionStats::new(1, 0, 0, 1, 0, 1, 0, 1),
-    MissionStats::new(1, 0, 0, 0, 1, 1, 0, 0),
     MissionStats::new(2, 1, 1, 0, 0, 0, 0, 0),
     MissionStats::new(2, 1, 0, 0, 0, 1, 0, 0),
     MissionStats::new(2, 0, 2, 1, 0, 0, 0, 0),
@@ -58,101 +69,55 @@ pub const SA_COMBINATIONS: [MissionStats; 27] = [
     MissionStats::new(3, 0, 0, 1, 0, 0, 0, 0),
 ];
 
+/// Missions in story order, with the index of their stat offsets in `MAP_OFFSETS`.
+/// The tutorial has no rating.
+pub const MISSIONS: [Mission; 21] = [
+    Mission::new("C0-1\\", 0, "The Gontranno Sanctuary", "Sicily").without_ratings(),
+    Mission::new("C1-1\\", 1, "Anathema", "Sicily").with_map_index(0),
+    Mission::new("C2-1\\", 2, "St. Petersburg Stakeout", "St. Petersburg").with_map_index(1),
+    Mission::new("C2-2\\", 2, "Kirov Park Meeting", "St. Petersburg").with_map_index(2),
+    Mission::new("C2-3\\", 2, "Tubeway Torpedo", "St. Petersburg").with_map_index(3),
+    Mission::new("C2-4\\", 2, "Invitation to a Party", "St. Petersburg").with_map_index(4),
+    Mission::new("C3-1\\", 3, "Tracking Hayamoto", "Japan").with_map_index(5),
+    Mission::new("C3-2a", 3, "Hidden Valley", "Japan")
+        .with_map_index(6)
+        .with_sa_notes("No target, the mission ends at the gates of the fortress"),
+    Mission::new("C3-2b", 3, "At the Gates", "Japan").with_map_index(7),
+    Mission::new("C3-3\\", 3, "Shogun Showdown", "Japan").with_map_index(8),
+    Mission::new("C4-1\\", 4, "Basement Killing", "Malaysia").with_map_index(9),
+    Mission::new("C4-2\\", 4, "The Graveyard Shift", "Malaysia").with_map_index(10),
+    Mission::new("C4-3\\", 4, "The Jacuzzi Job", "Malaysia").with_map_index(11),
+    Mission::new("C5-1\\", 5, "Murder At The Bazaar", "Afghanistan").with_map_index(12),
+    Mission::new("C5-2\\", 5, "The Motorcade Interception", "Afghanistan").with_map_index(13),
+    Mission::new("C5-3\\", 5, "Tunnel Rat", "Afghanistan").with_map_index(14),
+    Mission::new("C6-1\\", 6, "Temple City Ambush", "India").with_map_index(15),
+    Mission::new("C6-2\\", 6, "The Death of Hannelore", "India").with_map_index(16),
+    Mission::new("C6-3\\", 6, "Terminal Hospitality", "India").with_map_index(17),
+    Mission::new("C7-1\\", 7, "St. Petersburg Revisited", "St. Petersburg").with_map_index(18),
+    Mission::new("C8-1\\", 8, "Redemption at Gontranno", "Sicily").with_map_index(19),
+];
+
+/// Validated mission catalog of the game
+pub fn missions() -> &'static MissionTable {
+    static MISSION_TABLE: OnceLock<MissionTable> = OnceLock::new();
+    MISSION_TABLE.get_or_init(|| {
+        MissionTable::new(&MISSIONS, MAP_OFFSETS.len()).expect("Hitman 2 mission table is valid")
+    })
+}
+
 pub struct Hm2 {
     pid: u32,
-    map_decoder: HashMap<String, (String, Option<usize>)>,
     shots_fired_backup: u32,
+    last_map_code: String,
 }
 
 impl Hm2 {
     pub fn new(pid: u32) -> Self {
         Hm2 {
             pid,
-            map_decoder: HashMap::from([
-                (
-                    String::from("C0-1\\"),
-                    (String::from("The Gontranno Sanctuary"), None),
-                ),
-                (String::from("C1-1\\"), (String::from("Anathema"), Some(0))),
-                (
-                    String::from("C2-1\\"),
-                    (String::from("St. Petersburg Stakeout"), Some(1)),
-                ),
-                (
-                    String::from("C2-2\\"),
-                    (String::from("Kirov Park Meeting"), Some(2)),
-                ),
-                (
-                    String::from("C2-3\\"),
-                    (String::from("Tubeway Torpedo"), Some(3)),
-                ),
-                (
-                    String::from("C2-4\\"),
-                    (String::from("Invitation to a Party"), Some(4)),
-                ),
-                (
-                    String::from("C3-1\\"),
-                    (String::from("Tracking Hayamoto"), Some(5)),
-                ),
-                (
-                    String::from("C3-2a"),
-                    (String::from("Hidden Valley"), Some(6)),
-                ),
-                (
-                    String::from("C3-2b"),
-                    (String::from("At the Gates"), Some(7)),
-                ),
-                (
-                    String::from("C3-3\\"),
-                    (String::from("Shogun Showdown"), Some(8)),
-                ),
-                (
-                    String::from("C4-1\\"),
-                    (String::from("Basement Killing"), Some(9)),
-                ),
-                (
-                    String::from("C4-2\\"),
-                    (String::from("The Graveyard Shift"), Some(10)),
-                ),
-                (
-                    String::from("C4-3\\"),
-                    (String::from("The Jacuzzi Job"), Some(11)),
-                ),
-                (
-                    String::from("C5-1\\"),
-                    (String::from("Murder At The Bazaar"), Some(12)),
-                ),
-                (
-                    String::from("C5-2\\"),
-                    (String::from("The Motorcade Interception"), Some(13)),
-                ),
-                (
-                    String::from("C5-3\\"),
-                    (String::from("Tunnel Rat"), Some(14)),
-                ),
-                (
-                    String::from("C6-1\\"),
-                    (String::from("Temple City Ambush"), Some(15)),
-                ),
-                (
-                    String::from("C6-2\\"),
-                    (String::from("The Death of Hannelore"), Some(16)),
-                ),
-                (
-                    String::from("C6-3\\"),
-                    (String::from("Terminal Hospitality"), Some(17)),
-                ),
-                (
-                    String::from("C7-1\\"),
-                    (String::from("St. Petersburg Revisited"), Some(18)),
-                ),
-                (
-                    String::from("C8-1\\"),
-                    (String::from("Redemption at Gontranno"), Some(19)),
-                ),
-            ]),
             // Shots fired memory location is somewhat volatile so we need a backup
             shots_fired_backup: 0,
+            last_map_code: String::new(),
         }
     }
 
@@ -170,7 +135,13 @@ impl Hm2 {
             SHOTS_FIRED.to_vec(),
         )) {
             Some(shots) => shots,
-            None => self.shots_fired_backup,
+            None => {
+                debug!(
+                    backup = self.shots_fired_backup,
+                    "Using the backup of shots fired"
+                );
+                self.shots_fired_backup
+            }
         };
 
         // Remaing stats are dependent on the map
@@ -189,12 +160,21 @@ impl Hm2 {
             let mut offset = *stat;
             offset[1] = MAP_OFFSETS[map_no];
 
-            stats[i + 1] = decode_to_u32(read_memory(
+            stats[i + 1] = match decode_to_u32(read_memory(
                 BASE_ADDRESS + DATA_ADDRESS,
                 self.pid,
                 4,
                 offset.to_vec(),
-            ))?;
+            )) {
+                Some(value) => value,
+                None => {
+                    debug!(
+                        stat = crate::STAT_NAMES[i + 1],
+                        "Failed to read mission stat"
+                    );
+                    return None;
+                }
+            };
         }
         return Some(MissionStats::from_array(stats));
     }
@@ -205,14 +185,29 @@ impl Backend for Hm2 {
         // Get map bytes and decode
         let map_bytes = match read_memory(BASE_ADDRESS + MAP_ADDRESS, self.pid, 5, MAP.to_vec()) {
             Ok(bytes) => bytes,
-            Err(_) => return None,
+            Err(err) => {
+                info!(pid = self.pid, %err, "Failed to read the map, the game has closed");
+                return None;
+            }
         };
 
-        let (map_name, ratings, enable_timer) =
-            match self.map_decoder.get(&decode_to_string(map_bytes)?) {
-                Some((map, ratings)) => (map.as_str(), ratings, true),
-                None => ("Hitman 2 SA", &None, false),
-            };
+        let map_code = match decode_to_string(map_bytes) {
+            Some(map_code) => map_code,
+            None => {
+                debug!(pid = self.pid, "Failed to decode the map code");
+                return None;
+            }
+        };
+        let (map_name, ratings, enable_timer) = match missions().get(&map_code) {
+            Some(mission) => (mission.name, mission.map_index, true),
+            None => ("Hitman 2 SA", None, false),
+        };
+        log_map_change(
+            &mut self.last_map_code,
+            &map_code,
+            enable_timer.then_some(map_name),
+        );
+        let mission_code = enable_timer.then_some(map_code);
 
         if enable_timer {
             // Get mission timer
@@ -228,7 +223,7 @@ impl Backend for Hm2 {
 
             // Only get ratings if they are active on current map
             if mission_time > 0 {
-                match *ratings {
+                match ratings {
                     Some(map_no) => {
                         // Get game stats
                         let stats = self.load_stats(map_no)?;
@@ -237,29 +232,46 @@ impl Backend for Hm2 {
                         self.shots_fired_backup = stats.shots_fired;
 
                         // Check for SA rating
-                        let mut silent_assasin = false;
-
-                        for combination in SA_COMBINATIONS {
-                            if sa_compare(stats, combination) {
-                                silent_assasin = true;
-                                break;
-                            }
-                        }
+                        let silent_assasin = sa_risk(stats, &SA_COMBINATIONS);
 
                         return Some(GameData::new(
+                            mission_code,
                             map_name.to_string(),
-                            mission_time,
+                            mission_time as f32,
                             Some(Rating::new(stats, silent_assasin)),
                         ));
                     }
-                    None => return Some(GameData::new(map_name.to_string(), mission_time, None)),
+                    None => {
+                        return Some(GameData::new(
+                            mission_code,
+                            map_name.to_string(),
+                            mission_time as f32,
+                            None,
+                        ))
+                    }
                 }
             }
         }
-        return Some(GameData::new(map_name.to_string(), 0, None));
+        return Some(GameData::new(mission_code, map_name.to_string(), 0.0, None));
     }
 
     fn game_window(&self) -> Option<Window> {
-        get_process_window("Hitman2")
+        get_process_window(self.pid)
+    }
+
+    fn missions(&self) -> &'static MissionTable {
+        missions()
+    }
+
+    fn game_name(&self) -> &'static str {
+        GAME_NAME
+    }
+
+    fn difficulties(&self) -> &'static [Difficulty] {
+        &DIFFICULTIES
+    }
+
+    fn pid(&self) -> u32 {
+        self.pid
     }
 }
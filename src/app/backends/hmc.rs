@@ -1,7 +1,20 @@
-use super::backend_helpers::sa_compare;
-use crate::app::{memory::*, system_access::get_process_window};
-use crate::{Backend, GameData, MissionStats, Window};
-use std::collections::HashMap;
+use super::backend_helpers::{log_map_change, sa_risk};
+use super::missions::{Mission, MissionTable};
+use crate::app::{memory::*, system_access::get_process_window, time_format::TICKS_PER_SECOND};
+use crate::{Backend, Difficulty, GameData, MissionStats, Window};
+use std::sync::OnceLock;
+use tracing::{debug, info};
+
+/// Full title of the game, as used by split files
+pub const GAME_NAME: &str = "Hitman: Contracts";
+
+/// Difficulty levels offered by the game, from easiest to hardest
+pub const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Rookie,
+    Difficulty::Normal,
+    Difficulty::Expert,
+    Difficulty::Professional,
+];
 
 /// Memory addresses
 const BASE_ADDRESS: usize = 0x400000;
@@ -23,7 +36,8 @@ const INNOCENTS_KILLED: [usize; 1] = [0xB27];
 const INNOCENTS_HARMED: [usize; 1] = [0xB23];
 
 /// Valid silent assassin combinations
-const SA_COMBINATIONS: [MissionStats; 17] = [
+/// The first entry allows up to 999 shots while nobody is hurt and no one is met up close
+pub const SA_COMBINATIONS: [MissionStats; 17] = [
     MissionStats::new(999, 0, 999, 1, 0, 0, 0, 0),
     MissionStats::new(2, 1, 1, 0, 0, 0, 0, 0),
     MissionStats::new(2, 1, 0, 0, 0, 1, 0, 0),
@@ -43,39 +57,40 @@ const SA_COMBINATIONS: [MissionStats; 17] = [
     MissionStats::new(0, 0, 0, 1, 0, 6, 0, 0),
 ];
 
+/// Missions in story order, Contracts has no map dependent stat offsets.
+/// Deadly Cargo is not listed, as its map code has not been confirmed.
+pub const MISSIONS: [Mission; 12] = [
+    Mission::new("C00-1", 0, "Training", "Romania").without_ratings(),
+    Mission::new("C01-1", 1, "Asylum Aftermath", "Romania"),
+    Mission::new("C01-2", 1, "The Meat King's Party", "Romania"),
+    Mission::new("C02-1", 2, "The Bjarkhov Bomb", "Siberia"),
+    Mission::new("C03-1", 3, "Beldingford Manor", "England"),
+    Mission::new("C06-1", 6, "Rendezvous in Rotterdam", "Rotterdam"),
+    Mission::new("C07-1", 7, "Traditions of the Trade", "Paris"),
+    Mission::new("C08-1", 8, "Slaying a Dragon", "Hong Kong"),
+    Mission::new("C08-2", 8, "The Wang Fou Incident", "Hong Kong"),
+    Mission::new("C08-3", 8, "The Seafood Massacre", "Hong Kong"),
+    Mission::new("C08-4", 8, "Lee Hong Assassination", "Hong Kong"),
+    Mission::new("C09-1", 9, "Hunter and Hunted", "Paris"),
+];
+
+/// Validated mission catalog of the game
+pub fn missions() -> &'static MissionTable {
+    static MISSION_TABLE: OnceLock<MissionTable> = OnceLock::new();
+    MISSION_TABLE
+        .get_or_init(|| MissionTable::new(&MISSIONS, 0).expect("Contracts mission table is valid"))
+}
+
 pub struct HmC {
     pid: u32,
-    map_decoder: HashMap<String, String>,
+    last_map_code: String,
 }
 
 impl HmC {
     pub fn new(pid: u32) -> Self {
         HmC {
             pid,
-            map_decoder: HashMap::from([
-                (String::from("C00-1"), String::from("Training")),
-                (String::from("C01-1"), String::from("Asylum Aftermatch")),
-                (String::from("C01-2"), String::from("The Meat King's Party")),
-                (String::from("C02-1"), String::from("The Bjarkhov Bomb")),
-                (String::from("C03-1"), String::from("Beldingford Manor")),
-                (
-                    String::from("C06-1"),
-                    String::from("Rendezvous in Rotterdam"),
-                ),
-                (String::from("C06-1"), String::from("Deadly Cargo")),
-                (
-                    String::from("C07-1"),
-                    String::from("Traditions of the Trade"),
-                ),
-                (String::from("C08-1"), String::from("Slaying a Dragon")),
-                (String::from("C08-2"), String::from("The Wang Fou Incident")),
-                (String::from("C08-3"), String::from("The Seafood Massacre")),
-                (
-                    String::from("C08-4"),
-                    String::from("Lee Hong Assassination"),
-                ),
-                (String::from("C09-1"), String::from("Hunter and Hunted")),
-            ]),
+            last_map_code: String::new(),
         }
     }
 
@@ -83,12 +98,18 @@ impl HmC {
         let mut stats = [0; 8];
 
         // Shots has no map dependent pointer
-        stats[0] = decode_to_u32(read_memory(
+        stats[0] = match decode_to_u32(read_memory(
             BASE_ADDRESS + SHOTS_ADDRESS,
             self.pid,
             4,
             SHOTS_FIRED.to_vec(),
-        ))?;
+        )) {
+            Some(shots) => shots,
+            None => {
+                debug!(stat = crate::STAT_NAMES[0], "Failed to read mission stat");
+                return None;
+            }
+        };
 
         // Remaing stats are dependent on the map
         for (i, stat) in [
@@ -103,12 +124,21 @@ impl HmC {
         .iter()
         .enumerate()
         {
-            stats[i + 1] = decode_to_u32(read_memory(
+            stats[i + 1] = match decode_to_u32(read_memory(
                 BASE_ADDRESS + DATA_ADDRESS,
                 self.pid,
                 4,
                 stat.to_vec(),
-            ))?;
+            )) {
+                Some(value) => value,
+                None => {
+                    debug!(
+                        stat = crate::STAT_NAMES[i + 1],
+                        "Failed to read mission stat"
+                    );
+                    return None;
+                }
+            };
         }
         return Some(MissionStats::from_array(stats));
     }
@@ -119,13 +149,29 @@ impl Backend for HmC {
         // Get map bytes and decode
         let map_bytes = match read_memory(BASE_ADDRESS + MAP_ADDRESS, self.pid, 5, MAP.to_vec()) {
             Ok(bytes) => bytes,
-            Err(_) => return None,
+            Err(err) => {
+                info!(pid = self.pid, %err, "Failed to read the map, the game has closed");
+                return None;
+            }
         };
 
-        let (map_name, enable_timer) = match self.map_decoder.get(&decode_to_string(map_bytes)?) {
-            Some(map) => (map.as_str(), true),
-            None => ("Hitman Contracts", false),
+        let map_code = match decode_to_string(map_bytes) {
+            Some(map_code) => map_code,
+            None => {
+                debug!(pid = self.pid, "Failed to decode the map code");
+                return None;
+            }
+        };
+        let (map_name, ratings, enable_timer) = match missions().get(&map_code) {
+            Some(mission) => (mission.name, mission.ratings, true),
+            None => ("Hitman Contracts", false, false),
         };
+        log_map_change(
+            &mut self.last_map_code,
+            &map_code,
+            enable_timer.then_some(map_name),
+        );
+        let mission_code = enable_timer.then_some(map_code);
 
         if enable_timer {
             // Get mission timer
@@ -135,36 +181,55 @@ impl Backend for HmC {
                 4,
                 TIMER.to_vec(),
             )) {
-                Some(time) => (60.0 * time) as u32,
-                None => 0,
+                Some(time) => TICKS_PER_SECOND * time,
+                None => 0.0,
             };
 
             // Only get ratings if they are active on current map
-            if mission_time > 0 {
+            if mission_time > 0.0 {
+                if !ratings {
+                    return Some(GameData::new(
+                        mission_code,
+                        map_name.to_string(),
+                        mission_time,
+                        None,
+                    ));
+                }
+
                 // Get game stats
                 let stats = self.load_stats()?;
 
                 // Check silent assasin rating
-                let mut silent_assasin = false;
-
-                for combination in SA_COMBINATIONS {
-                    if sa_compare(stats, combination) {
-                        silent_assasin = true;
-                        break;
-                    }
-                }
+                let silent_assasin = sa_risk(stats, &SA_COMBINATIONS);
 
                 return Some(GameData::new(
+                    mission_code,
                     map_name.to_string(),
                     mission_time,
                     Some(crate::Rating::new(stats, silent_assasin)),
                 ));
             }
         }
-        return Some(GameData::new(map_name.to_string(), 0, None));
+        return Some(GameData::new(mission_code, map_name.to_string(), 0.0, None));
     }
 
     fn game_window(&self) -> Option<Window> {
-        get_process_window("Hitman Contracts")
+        get_process_window(self.pid)
+    }
+
+    fn missions(&self) -> &'static MissionTable {
+        missions()
+    }
+
+    fn game_name(&self) -> &'static str {
+        GAME_NAME
+    }
+
+    fn difficulties(&self) -> &'static [Difficulty] {
+        &DIFFICULTIES
+    }
+
+    fn pid(&self) -> u32 {
+        self.pid
     }
 }
@@ -0,0 +1,297 @@
+use super::{hm2, hmc};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A mission of a game, identified by the map code read from the game memory
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Mission {
+    pub code: &'static str,
+    /// Chapter of the mission as numbered by the game, missions at the same location
+    /// usually share a chapter
+    pub chapter: u32,
+    pub name: &'static str,
+    /// Country or city of the mission, empty if unknown
+    pub location: &'static str,
+    /// Index into the map dependent stat offsets of the game, if the stats of the mission are
+    /// read per map. Missions of games without map dependent offsets have no index.
+    pub map_index: Option<usize>,
+    /// Whether the game rates the mission, which tutorials do not
+    pub ratings: bool,
+    /// Hints on the silent assassin rating, only given for missions where it differs from the
+    /// usual rules and empty otherwise. The catalog holds no difficulty information, as the
+    /// difficulty is selected for the whole game.
+    pub sa_notes: &'static str,
+}
+
+impl Mission {
+    /// A rated mission without map dependent stat offsets
+    pub const fn new(
+        code: &'static str,
+        chapter: u32,
+        name: &'static str,
+        location: &'static str,
+    ) -> Self {
+        Mission {
+            code,
+            chapter,
+            name,
+            location,
+            map_index: None,
+            ratings: true,
+            sa_notes: "",
+        }
+    }
+
+    pub const fn with_map_index(mut self, index: usize) -> Self {
+        self.map_index = Some(index);
+        self
+    }
+
+    pub const fn without_ratings(mut self) -> Self {
+        self.ratings = false;
+        self
+    }
+
+    pub const fn with_sa_notes(mut self, notes: &'static str) -> Self {
+        self.sa_notes = notes;
+        self
+    }
+}
+
+/// Reasons for a mission table to be invalid
+#[derive(PartialEq, Debug)]
+pub enum MissionTableError {
+    DuplicateCode(&'static str),
+    OutOfOrder(&'static str),
+    DuplicateMapIndex(usize),
+    MapIndexOutOfRange(&'static str, usize),
+    MissingMapIndex(&'static str),
+    UnratedMapIndex(&'static str),
+}
+
+impl fmt::Display for MissionTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MissionTableError::DuplicateCode(code) => write!(f, "duplicate mission code {code:?}"),
+            MissionTableError::OutOfOrder(code) => {
+                write!(f, "mission {code:?} is not in chapter order")
+            }
+            MissionTableError::DuplicateMapIndex(index) => {
+                write!(f, "map offset {index} is used by several missions")
+            }
+            MissionTableError::MapIndexOutOfRange(code, index) => {
+                write!(f, "map offset {index} of mission {code:?} does not exist")
+            }
+            MissionTableError::MissingMapIndex(code) => {
+                write!(f, "rated mission {code:?} has no map offset")
+            }
+            MissionTableError::UnratedMapIndex(code) => {
+                write!(f, "mission {code:?} without ratings has a map offset")
+            }
+        }
+    }
+}
+
+/// Validated list of the missions of a game in story order, with lookup by map code
+pub struct MissionTable {
+    missions: &'static [Mission],
+    by_code: HashMap<&'static str, usize>,
+}
+
+impl MissionTable {
+    /// Validate the missions of a game: codes must be unique and chapters in order, each
+    /// map offset of the game may only be used by a single mission, and in games with map
+    /// offsets exactly the rated missions have one.
+    pub fn new(
+        missions: &'static [Mission],
+        map_offsets: usize,
+    ) -> Result<MissionTable, MissionTableError> {
+        let mut by_code = HashMap::new();
+        let mut used_offsets = vec![false; map_offsets];
+        let mut previous = None;
+
+        for (i, mission) in missions.iter().enumerate() {
+            if by_code.insert(mission.code, i).is_some() {
+                return Err(MissionTableError::DuplicateCode(mission.code));
+            }
+
+            if previous.is_some_and(|previous| previous > mission.chapter) {
+                return Err(MissionTableError::OutOfOrder(mission.code));
+            }
+            previous = Some(mission.chapter);
+
+            match mission.map_index {
+                Some(_) if !mission.ratings => {
+                    return Err(MissionTableError::UnratedMapIndex(mission.code))
+                }
+                Some(index) => match used_offsets.get_mut(index) {
+                    Some(true) => return Err(MissionTableError::DuplicateMapIndex(index)),
+                    Some(used) => *used = true,
+                    None => return Err(MissionTableError::MapIndexOutOfRange(mission.code, index)),
+                },
+                None if mission.ratings && map_offsets > 0 => {
+                    return Err(MissionTableError::MissingMapIndex(mission.code))
+                }
+                None => {}
+            }
+        }
+
+        Ok(MissionTable { missions, by_code })
+    }
+
+    /// Get the mission of a map code read from the game
+    pub fn get(&self, code: &str) -> Option<&'static Mission> {
+        self.by_code.get(code).map(|&i| &self.missions[i])
+    }
+
+    /// Position of a mission in the story, starting at 0
+    pub fn story_order(&self, code: &str) -> Option<usize> {
+        self.by_code.get(code).copied()
+    }
+
+    /// Whether the mission `next` directly follows the mission `code` in the story
+    pub fn is_next(&self, code: &str, next: &str) -> bool {
+        self.story_order(code)
+            .zip(self.story_order(next))
+            .is_some_and(|(order, next)| order + 1 == next)
+    }
+
+    /// Whether the mission is the last one of the story
+    pub fn is_final(&self, code: &str) -> bool {
+        self.story_order(code)
+            .is_some_and(|order| order + 1 == self.missions.len())
+    }
+
+    /// All missions in story order
+    pub fn missions(&self) -> &'static [Mission] {
+        self.missions
+    }
+
+    /// Mission codes and names of all missions in story order
+    pub fn mission_list(&self) -> Vec<(String, String)> {
+        self.missions
+            .iter()
+            .map(|mission| (mission.code.to_string(), mission.name.to_string()))
+            .collect()
+    }
+}
+
+/// Mission catalogs of all supported games
+pub fn catalogs() -> [&'static MissionTable; 2] {
+    [hm2::missions(), hmc::missions()]
+}
+
+/// Find a mission of any game by its map code, as the games use different code formats
+pub fn find_mission(code: &str) -> Option<&'static Mission> {
+    catalogs().into_iter().find_map(|catalog| catalog.get(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_tables_are_valid() {
+        assert!(MissionTable::new(&hm2::MISSIONS, hm2::MAP_OFFSETS.len()).is_ok());
+        assert!(MissionTable::new(&hmc::MISSIONS, 0).is_ok());
+    }
+
+    #[test]
+    fn every_rated_mission_has_map_offsets() {
+        // Every map offset of Hitman 2 belongs to a mission
+        let indices: Vec<usize> = hm2::MISSIONS.iter().filter_map(|m| m.map_index).collect();
+        assert_eq!(indices.len(), hm2::MAP_OFFSETS.len());
+    }
+
+    #[test]
+    fn catalogs_describe_every_mission() {
+        for catalog in catalogs() {
+            for (order, mission) in catalog.missions().iter().enumerate() {
+                assert_eq!(find_mission(mission.code), Some(mission));
+                assert_eq!(catalog.story_order(mission.code), Some(order));
+            }
+        }
+
+        let table = hm2::missions();
+        assert!(table.is_next("C1-1\\", "C2-1\\"));
+        assert!(!table.is_next("C1-1\\", "C2-2\\"));
+        assert!(!table.is_next("C2-1\\", "C1-1\\"));
+        assert!(table.is_final(hm2::MISSIONS.last().unwrap().code));
+        assert!(!table.is_final("C1-1\\"));
+
+        let tutorial = find_mission("C0-1\\").unwrap();
+        assert!(!tutorial.ratings);
+        assert_eq!(tutorial.chapter, 0);
+        assert_eq!(find_mission("C08-4").unwrap().location, "Hong Kong");
+        assert_eq!(find_mission("C3-2b").unwrap().chapter, 3);
+        assert_eq!(find_mission("Menu"), None);
+    }
+
+    #[test]
+    fn contracts_mission_table() {
+        let table = hmc::missions();
+        assert_eq!(table.missions().len(), 12);
+        assert_eq!(table.get("C06-1").unwrap().name, "Rendezvous in Rotterdam");
+        assert!(!table.get("C00-1").unwrap().ratings);
+        assert_eq!(table.get("C01-1").unwrap().name, "Asylum Aftermath");
+    }
+
+    #[test]
+    fn duplicates_are_rejected() {
+        static CODES: [Mission; 3] = [
+            Mission::new("C06-1", 6, "Rendezvous in Rotterdam", "Rotterdam"),
+            Mission::new("C06-1", 6, "Deadly Cargo", "Rotterdam"),
+            Mission::new("C07-1", 7, "Traditions of the Trade", "Paris"),
+        ];
+        assert_eq!(
+            MissionTable::new(&CODES, 0).err(),
+            Some(MissionTableError::DuplicateCode("C06-1"))
+        );
+
+        static OFFSETS: [Mission; 2] = [
+            Mission::new("C1-1\\", 1, "Anathema", "Sicily").with_map_index(0),
+            Mission::new("C2-1\\", 2, "St. Petersburg Stakeout", "St. Petersburg")
+                .with_map_index(0),
+        ];
+        assert_eq!(
+            MissionTable::new(&OFFSETS, 2).err(),
+            Some(MissionTableError::DuplicateMapIndex(0))
+        );
+    }
+
+    #[test]
+    fn invalid_tables_are_rejected() {
+        static ORDER: [Mission; 2] = [
+            Mission::new("C3-1\\", 3, "Tracking Hayamoto", "Japan"),
+            Mission::new("C2-4\\", 2, "Invitation to a Party", "St. Petersburg"),
+        ];
+        assert_eq!(
+            MissionTable::new(&ORDER, 0).err(),
+            Some(MissionTableError::OutOfOrder("C2-4\\"))
+        );
+
+        static RANGE: [Mission; 1] =
+            [Mission::new("C1-1\\", 1, "Anathema", "Sicily").with_map_index(20)];
+        assert_eq!(
+            MissionTable::new(&RANGE, 20).err(),
+            Some(MissionTableError::MapIndexOutOfRange("C1-1\\", 20))
+        );
+
+        static MISSING: [Mission; 1] = [Mission::new("C1-1\\", 1, "Anathema", "Sicily")];
+        assert_eq!(
+            MissionTable::new(&MISSING, 1).err(),
+            Some(MissionTableError::MissingMapIndex("C1-1\\"))
+        );
+
+        static UNRATED: [Mission; 1] =
+            [
+                Mission::new("C0-1\\", 0, "The Gontranno Sanctuary", "Sicily")
+                    .without_ratings()
+                    .with_map_index(0),
+            ];
+        assert_eq!(
+            MissionTable::new(&UNRATED, 1).err(),
+            Some(MissionTableError::UnratedMapIndex("C0-1\\"))
+        );
+    }
+}
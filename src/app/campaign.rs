@@ -0,0 +1,202 @@
+use super::backends::missions::MissionTable;
+use crate::{GameData, MissionStats, SaRisk};
+
+use std::collections::HashMap;
+
+/// Result of a single mission within a campaign run
+#[derive(Clone)]
+pub struct Split {
+    pub mission_code: String,
+    pub time: f32,
+    pub stats: MissionStats,
+    pub sa_risk: Option<SaRisk>,
+    pub delta: Option<f32>,
+}
+
+impl Split {
+    fn from_game_data(data: &GameData) -> Self {
+        Split {
+            mission_code: data.mission_code.clone().unwrap_or_default(),
+            time: data.mission_time,
+            stats: data.rating.map(|r| r.stats).unwrap_or_default(),
+            sa_risk: data.rating.map(|r| r.sa_risk),
+            delta: None,
+        }
+    }
+}
+
+/// Tracks a sequence of missions played in a row, e.g. a full game SA run.
+/// The games only load the next story mission after the level is completed, so a mission is
+/// completed and added to the splits once the following mission starts. The final mission
+/// has no following mission, it is completed once the game returns to the menus, which
+/// cannot be told apart from quitting it. Switching to any other mission abandons it.
+#[derive(Default)]
+pub struct CampaignRun {
+    splits: Vec<Split>,
+    current: Option<Split>,
+}
+
+impl CampaignRun {
+    /// Update the run from the latest game data
+    pub fn update(
+        &mut self,
+        data: &GameData,
+        missions: &MissionTable,
+        best_times: &mut HashMap<String, f32>,
+    ) {
+        // Keep the current mission while in the menus or loading
+        if data.mission_time <= 0.0 {
+            let final_mission = self
+                .current
+                .as_ref()
+                .is_some_and(|current| missions.is_final(&current.mission_code));
+            if final_mission && data.mission_code.is_none() {
+                self.finish(best_times);
+            }
+            return;
+        }
+
+        let Some(code) = data.mission_code.as_deref() else {
+            return;
+        };
+
+        match self.current.as_mut() {
+            // Restarting the same mission simply overwrites the current attempt
+            Some(current) if current.mission_code == code => {
+                *current = Split::from_game_data(data);
+            }
+            Some(current) => {
+                // Abandoned missions do not count as splits or best times
+                if missions.is_next(&current.mission_code, code) {
+                    self.finish(best_times);
+                }
+                self.current = Some(Split::from_game_data(data));
+            }
+            None => self.current = Some(Split::from_game_data(data)),
+        }
+    }
+
+    /// Complete the current mission and add it to the splits
+    pub fn finish(&mut self, best_times: &mut HashMap<String, f32>) {
+        if let Some(mut split) = self.current.take() {
+            split.delta = best_times
+                .get(&split.mission_code)
+                .map(|best| split.time - best);
+
+            if split.delta.map_or(true, |delta| delta < 0.0) {
+                best_times.insert(split.mission_code.clone(), split.time);
+            }
+
+            // Replaying a mission replaces its previous split
+            self.splits.retain(|s| s.mission_code != split.mission_code);
+            self.splits.push(split);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.splits.clear();
+        self.current = None;
+    }
+
+    /// Get the completed split of a mission code, or the current attempt if it is running
+    pub fn get_split(&self, code: &str) -> Option<(&Split, bool)> {
+        if let Some(current) = self.current.as_ref().filter(|c| c.mission_code == code) {
+            return Some((current, true));
+        }
+
+        self.splits
+            .iter()
+            .find(|s| s.mission_code == code)
+            .map(|s| (s, false))
+    }
+
+    /// Total in-game time of the run, including the current mission
+    pub fn total_time(&self) -> f32 {
+        self.all_splits().map(|s| s.time).sum()
+    }
+
+    /// Sum of the stats of all missions in the run, including the current mission
+    pub fn total_stats(&self) -> MissionStats {
+        let mut total = [0; 8];
+        for split in self.all_splits() {
+            for (sum, value) in total.iter_mut().zip(split.stats.to_array()) {
+                *sum += value;
+            }
+        }
+        MissionStats::from_array(total)
+    }
+
+    /// Number of missions with SA and the number of missions with a rating
+    pub fn sa_count(&self) -> (usize, usize) {
+        let rated = self.all_splits().filter_map(|s| s.sa_risk);
+        rated.fold((0, 0), |(sa, total), risk| {
+            (sa + (risk != SaRisk::Lost) as usize, total + 1)
+        })
+    }
+
+    fn all_splits(&self) -> impl Iterator<Item = &Split> {
+        self.splits.iter().chain(self.current.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::backends::hm2;
+    use crate::app::test_fixtures::{game_data, menus};
+
+    #[test]
+    fn next_story_mission_completes_the_split() {
+        let mut run = CampaignRun::default();
+        let mut best_times = HashMap::from([("C1-1\\".to_string(), 2000.0)]);
+
+        run.update(
+            &game_data("C1-1\\", 1500.0),
+            hm2::missions(),
+            &mut best_times,
+        );
+        run.update(&game_data("C2-1\\", 10.0), hm2::missions(), &mut best_times);
+
+        let (split, current) = run.get_split("C1-1\\").unwrap();
+        assert!(!current);
+        assert_eq!(split.delta, Some(-500.0));
+        assert_eq!(best_times["C1-1\\"], 1500.0);
+    }
+
+    #[test]
+    fn final_mission_is_completed_in_the_menus() {
+        let mut run = CampaignRun::default();
+        let mut best_times = HashMap::new();
+        let last = hm2::MISSIONS.last().unwrap().code;
+
+        run.update(&game_data(last, 4000.0), hm2::missions(), &mut best_times);
+        // Loading screens keep the mission code
+        run.update(&game_data(last, 0.0), hm2::missions(), &mut best_times);
+        assert!(run.get_split(last).unwrap().1);
+
+        run.update(&menus(), hm2::missions(), &mut best_times);
+
+        let (split, current) = run.get_split(last).unwrap();
+        assert!(!current);
+        assert_eq!(split.time, 4000.0);
+        assert_eq!(best_times[last], 4000.0);
+    }
+
+    #[test]
+    fn abandoned_mission_is_not_recorded() {
+        let mut run = CampaignRun::default();
+        let mut best_times = HashMap::new();
+
+        // Abandon the first mission early and switch to a later one
+        run.update(
+            &game_data("C1-1\\", 300.0),
+            hm2::missions(),
+            &mut best_times,
+        );
+        run.update(&game_data("C2-3\\", 10.0), hm2::missions(), &mut best_times);
+
+        assert!(run.get_split("C1-1\\").is_none());
+        assert!(best_times.is_empty());
+        assert_eq!(run.total_time(), 10.0);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::SaRisk;
 use egui::Color32;
 
 /// Struct to easily store the ColorMaps for the Silent Assassin status
@@ -5,6 +6,8 @@ use egui::Color32;
 pub struct ColorMap {
     label: String,
     sa_true: Color32,
+    #[serde(default = "ColorMap::default_sa_risk")]
+    sa_risk: Color32,
     sa_false: Color32,
 }
 
@@ -26,6 +29,7 @@ impl ColorMap {
         ColorMap {
             label: String::from("Green / Red"),
             sa_true: Color32::from_rgb(0, 160, 0),
+            sa_risk: Color32::from_rgb(230, 160, 0),
             sa_false: Color32::RED,
         }
     }
@@ -35,6 +39,7 @@ impl ColorMap {
         ColorMap {
             label: String::from("Darker Green / Red"),
             sa_true: Color32::DARK_GREEN,
+            sa_risk: Color32::from_rgb(170, 110, 0),
             sa_false: Color32::DARK_RED,
         }
     }
@@ -44,6 +49,7 @@ impl ColorMap {
         ColorMap {
             label: String::from("Blue / Red"),
             sa_true: Color32::from_rgb(0, 90, 180),
+            sa_risk: Color32::from_rgb(240, 180, 0),
             sa_false: Color32::from_rgb(220, 50, 32),
         }
     }
@@ -53,6 +59,7 @@ impl ColorMap {
         ColorMap {
             label: String::from("Blue / Orange"),
             sa_true: Color32::from_rgb(12, 123, 220),
+            sa_risk: Color32::from_rgb(240, 228, 66),
             sa_false: Color32::from_rgb(255, 194, 10),
         }
     }
@@ -62,6 +69,7 @@ impl ColorMap {
         ColorMap {
             label: String::from("Blue / Brown"),
             sa_true: Color32::from_rgb(0, 108, 209),
+            sa_risk: Color32::from_rgb(230, 159, 0),
             sa_false: Color32::from_rgb(153, 79, 0),
         }
     }
@@ -71,16 +79,39 @@ impl ColorMap {
         ColorMap {
             label: String::from("Mint / Khaki"),
             sa_true: Color32::from_rgb(64, 176, 166),
+            sa_risk: Color32::from_rgb(150, 150, 150),
             sa_false: Color32::from_rgb(225, 190, 106),
         }
     }
 
+    /// User defined colormap
+    pub fn custom(label: String, sa_true: Color32, sa_risk: Color32, sa_false: Color32) -> Self {
+        ColorMap {
+            label,
+            sa_true,
+            sa_risk,
+            sa_false,
+        }
+    }
+
+    /// Risk color used for colormaps stored before the risk level was introduced
+    fn default_sa_risk() -> Color32 {
+        Color32::from_rgb(230, 160, 0)
+    }
+
+    /// Check if the colormap is one of the built-in presets
+    pub fn is_preset(&self) -> bool {
+        ColorMap::all_cmaps()
+            .iter()
+            .any(|preset| preset.label == self.label)
+    }
+
     // Getters
-    pub fn get_rating_color(&self, sa_status: bool) -> Color32 {
-        if sa_status {
-            self.sa_true
-        } else {
-            self.sa_false
+    pub fn get_rating_color(&self, sa_risk: SaRisk) -> Color32 {
+        match sa_risk {
+            SaRisk::Safe => self.sa_true,
+            SaRisk::AtRisk => self.sa_risk,
+            SaRisk::Lost => self.sa_false,
         }
     }
 
@@ -96,7 +127,49 @@ impl ColorMap {
         self.sa_true
     }
 
+    pub fn get_sa_risk(&self) -> Color32 {
+        self.sa_risk
+    }
+
     pub fn get_sa_false(&self) -> Color32 {
         self.sa_false
     }
 }
+
+/// Editable state of a user defined colormap before it is saved
+pub struct ColorMapEditor {
+    pub label: String,
+    pub sa_true: Color32,
+    pub sa_risk: Color32,
+    pub sa_false: Color32,
+}
+
+impl ColorMapEditor {
+    /// Start editing from an existing colormap, keeping the name only for user defined maps
+    pub fn from_cmap(cmap: &ColorMap) -> Self {
+        ColorMapEditor {
+            label: if cmap.is_preset() {
+                String::new()
+            } else {
+                cmap.get_label()
+            },
+            sa_true: cmap.get_sa_true(),
+            sa_risk: cmap.get_sa_risk(),
+            sa_false: cmap.get_sa_false(),
+        }
+    }
+
+    pub fn to_cmap(&self) -> ColorMap {
+        ColorMap::custom(
+            self.label.trim().to_string(),
+            self.sa_true,
+            self.sa_risk,
+            self.sa_false,
+        )
+    }
+
+    /// A colormap can be saved if it has a name that does not shadow a preset
+    pub fn is_valid(&self) -> bool {
+        !self.label.trim().is_empty() && !self.to_cmap().is_preset()
+    }
+}
@@ -1,3 +1,5 @@
+use egui::FontFamily;
+
 /// Add monospace font to existing font families to fonts and set as default for monospace
 pub fn load_monospace_font(ctx: &egui::Context) {
     let mut fonts = egui::FontDefinitions::default();
@@ -13,5 +15,44 @@ pub fn load_monospace_font(ctx: &egui::Context) {
         .or_default()
         .insert(0, "Monofonto".to_owned());
 
+    // Keep the default egui monospace font available for the overlay
+    fonts
+        .families
+        .insert(FontFamily::Name("Hack".into()), vec!["Hack".to_owned()]);
+
     ctx.set_fonts(fonts);
-}
\ No newline at end of file
+}
+
+/// Fonts that can be selected for the overlay text
+#[derive(PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
+pub enum OverlayFont {
+    Monofonto,
+    Hack,
+    Ubuntu,
+}
+
+impl OverlayFont {
+    pub fn all_fonts() -> [Self; 3] {
+        [
+            OverlayFont::Monofonto,
+            OverlayFont::Hack,
+            OverlayFont::Ubuntu,
+        ]
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            OverlayFont::Monofonto => "Monofonto",
+            OverlayFont::Hack => "Hack",
+            OverlayFont::Ubuntu => "Ubuntu",
+        }
+    }
+
+    pub fn family(&self) -> FontFamily {
+        match self {
+            OverlayFont::Monofonto => FontFamily::Monospace,
+            OverlayFont::Hack => FontFamily::Name("Hack".into()),
+            OverlayFont::Ubuntu => FontFamily::Proportional,
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use super::time_format::TICKS_PER_SECOND;
+use crate::{Backend, Difficulty, GameData, MissionStats};
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io, path::Path};
+
+// Field names of the stats in exported files, in the same order as `MissionStats::to_array`
+const STAT_KEYS: [&str; 8] = [
+    "shots_fired",
+    "close_encounters",
+    "headshots",
+    "alerts",
+    "enemies_killed",
+    "enemies_harmed",
+    "innocents_killed",
+    "innocents_harmed",
+];
+
+/// A single played mission, from the mission start until the mission is restarted, another
+/// mission is started or the game is closed
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Attempt {
+    pub game: String,
+    pub mission_code: String,
+    pub mission_name: String,
+    pub date: u64,
+    pub time: f32,
+    pub stats: MissionStats,
+    pub sa_rating: Option<bool>,
+    /// Index of the stat that lost the SA rating, in the order of `MissionStats::to_array`
+    #[serde(default)]
+    pub sa_breaker: Option<usize>,
+    /// Attempts stored before difficulties were tracked count as the default difficulty
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Set once the following story mission is loaded, which the games only allow after the
+    /// level is completed, or when the game returns to the menus after the final mission.
+    /// Attempts stored before completions were tracked count as abandoned.
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl Attempt {
+    fn start(data: &GameData, game: &dyn Backend, difficulty: Difficulty) -> Self {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        let mut attempt = Attempt {
+            game: game.game_name().to_string(),
+            mission_code: data.mission_code.clone().unwrap_or_default(),
+            mission_name: data.mission_name.clone(),
+            date,
+            time: 0.0,
+            stats: MissionStats::default(),
+            sa_rating: None,
+            sa_breaker: None,
+            difficulty,
+            completed: false,
+        };
+        attempt.update(data);
+        attempt
+    }
+
+    fn update(&mut self, data: &GameData) {
+        let stats = data.rating.map(|r| r.stats).unwrap_or_default();
+        let sa_rating = data.rating.map(|r| r.sa_rating);
+
+        // Remember the first stat that increased when the rating was lost
+        if self.sa_rating == Some(true) && sa_rating == Some(false) {
+            self.sa_breaker = stats
+                .to_array()
+                .iter()
+                .zip(self.stats.to_array())
+                .position(|(new, old)| *new > old);
+        }
+
+        self.time = data.mission_time;
+        self.stats = stats;
+        self.sa_rating = sa_rating;
+    }
+}
+
+/// Stored mission attempts across all sessions
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MissionHistory {
+    attempts: Vec<Attempt>,
+    #[serde(skip)]
+    current: Option<Attempt>,
+}
+
+impl MissionHistory {
+    /// Update the running attempt from the latest game data, played on the given difficulty
+    pub fn update(&mut self, data: &GameData, game: &dyn Backend, difficulty: Difficulty) {
+        let missions = game.missions();
+
+        // The attempt is kept while loading and in the menus, as it is only known to be
+        // completed once the next mission starts. The final mission has no next mission.
+        if data.mission_time <= 0.0 {
+            if let Some(current) = self.current.as_mut() {
+                if data.mission_code.is_none() && missions.is_final(&current.mission_code) {
+                    current.completed = true;
+                    self.finish();
+                }
+            }
+            return;
+        }
+
+        match self.current.as_mut() {
+            // A lower time than before means that the mission was restarted
+            Some(current)
+                if data.mission_code.as_deref() == Some(current.mission_code.as_str())
+                    && current.difficulty == difficulty
+                    && data.mission_time >= current.time =>
+            {
+                current.update(data);
+            }
+            _ => {
+                if let Some(current) = self.current.as_mut() {
+                    current.completed = data
+                        .mission_code
+                        .as_deref()
+                        .is_some_and(|code| missions.is_next(&current.mission_code, code));
+                }
+                self.finish();
+                self.current = Some(Attempt::start(data, game, difficulty));
+            }
+        }
+    }
+
+    /// Store the running attempt
+    pub fn finish(&mut self) {
+        if let Some(attempt) = self.current.take() {
+            self.attempts.push(attempt);
+        }
+    }
+
+    /// All stored attempts, oldest first
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    pub fn clear(&mut self) {
+        self.attempts.clear();
+    }
+
+    /// Write all stored attempts to a CSV file with a header row
+    pub fn export_csv(&self, path: &Path) -> io::Result<()> {
+        let mut csv = format!(
+            "game,mission_code,mission_name,difficulty,date,igt,{},silent_assassin,completed\n",
+            STAT_KEYS.join(",")
+        );
+
+        for attempt in &self.attempts {
+            let stats = attempt.stats.to_array().map(|value| value.to_string());
+            let sa_rating = attempt.sa_rating.map(|sa| sa.to_string());
+
+            csv.push_str(&format!(
+                "{},{},{},{},{},{:.3},{},{},{}\n",
+                csv_field(&attempt.game),
+                csv_field(&attempt.mission_code),
+                csv_field(&attempt.mission_name),
+                attempt.difficulty.get_label(),
+                format_date(attempt.date),
+                attempt.time / TICKS_PER_SECOND,
+                stats.join(","),
+                sa_rating.unwrap_or_default(),
+                attempt.completed,
+            ));
+        }
+
+        fs::write(path, csv)
+    }
+
+    /// Write all stored attempts to a JSON file as an array of objects
+    pub fn export_json(&self, path: &Path) -> io::Result<()> {
+        let rows: Vec<ExportRow> = self.attempts.iter().map(ExportRow::new).collect();
+        let json = serde_json::to_string_pretty(&rows)?;
+
+        fs::write(path, json + "\n")
+    }
+}
+
+/// Attempt as written to exported JSON files, with readable dates and times in seconds.
+/// Invalid times are written as `null`.
+#[derive(Serialize)]
+struct ExportRow<'a> {
+    game: &'a str,
+    mission_code: &'a str,
+    mission_name: &'a str,
+    difficulty: &'a str,
+    date: String,
+    igt: f32,
+    shots_fired: u32,
+    close_encounters: u32,
+    headshots: u32,
+    alerts: u32,
+    enemies_killed: u32,
+    enemies_harmed: u32,
+    innocents_killed: u32,
+    innocents_harmed: u32,
+    silent_assassin: Option<bool>,
+    completed: bool,
+}
+
+impl<'a> ExportRow<'a> {
+    fn new(attempt: &'a Attempt) -> Self {
+        let stats = attempt.stats;
+        ExportRow {
+            game: &attempt.game,
+            mission_code: &attempt.mission_code,
+            mission_name: &attempt.mission_name,
+            difficulty: attempt.difficulty.get_label(),
+            date: format_date(attempt.date),
+            igt: (attempt.time / TICKS_PER_SECOND * 1000.0).round() / 1000.0,
+            shots_fired: stats.shots_fired,
+            close_encounters: stats.close_encounters,
+            headshots: stats.headshots,
+            alerts: stats.alerts,
+            enemies_killed: stats.enemies_killed,
+            enemies_harmed: stats.enemies_harmed,
+            innocents_killed: stats.innocents_killed,
+            innocents_harmed: stats.innocents_harmed,
+            silent_assassin: attempt.sa_rating,
+            completed: attempt.completed,
+        }
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format a unix timestamp as an ISO 8601 UTC date and time
+pub fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Convert days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::backends::{hm2, missions::MissionTable};
+    use crate::app::test_fixtures;
+    use crate::Window;
+
+    /// Hitman 2 backend without a game process
+    struct TestGame;
+
+    impl Backend for TestGame {
+        fn update(&mut self) -> Option<GameData> {
+            None
+        }
+
+        fn game_window(&self) -> Option<Window> {
+            None
+        }
+
+        fn missions(&self) -> &'static MissionTable {
+            hm2::missions()
+        }
+
+        fn game_name(&self) -> &'static str {
+            hm2::GAME_NAME
+        }
+
+        fn difficulties(&self) -> &'static [Difficulty] {
+            &hm2::DIFFICULTIES
+        }
+
+        fn pid(&self) -> u32 {
+            0
+        }
+    }
+
+    /// Play the given game states, with the mission code and time of each update
+    fn play(states: &[(Option<&str>, f32)]) -> MissionHistory {
+        let mut history = MissionHistory::default();
+        for (code, time) in states {
+            let name = code.and_then(|code| hm2::missions().get(code));
+            let data = GameData::new(
+                code.map(String::from),
+                name.map_or(hm2::GAME_NAME.to_string(), |m| m.name.to_string()),
+                *time,
+                None,
+            );
+            history.update(&data, &TestGame, Difficulty::Normal);
+        }
+        history.finish();
+        history
+    }
+
+    fn named_attempt(mission_name: &str, time: f32) -> Attempt {
+        Attempt {
+            mission_name: mission_name.to_string(),
+            stats: MissionStats::new(2, 0, 1, 0, 1, 0, 0, 0),
+            difficulty: Difficulty::Expert,
+            ..test_fixtures::attempt("C1-1\\", 0, time, true)
+        }
+    }
+
+    #[test]
+    fn format_date_handles_leap_years() {
+        assert_eq!(format_date(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_date(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_date(951_868_799), "2000-02-29T23:59:59Z");
+        assert_eq!(format_date(1_709_164_800), "2024-02-29T00:00:00Z");
+        assert_eq!(format_date(1_709_251_200), "2024-03-01T00:00:00Z");
+        // 2100 is not a leap year
+        assert_eq!(format_date(4_107_456_000), "2100-02-28T00:00:00Z");
+        assert_eq!(format_date(4_107_542_400), "2100-03-01T00:00:00Z");
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("Anathema"), "Anathema");
+        assert_eq!(csv_field("Beldingford, Manor"), "\"Beldingford, Manor\"");
+        assert_eq!(csv_field("The \"Meat\" King"), "\"The \"\"Meat\"\" King\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn json_rows_are_escaped() {
+        let attempt = named_attempt("The \"Meat\" King's\\Party\n", 600.0);
+        let json = serde_json::to_string(&ExportRow::new(&attempt)).unwrap();
+
+        let row: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(row["mission_name"], "The \"Meat\" King's\\Party\n");
+        assert_eq!(row["difficulty"], "Expert");
+        assert_eq!(row["igt"], 10.0);
+        assert_eq!(row["headshots"], 1);
+        assert_eq!(row["silent_assassin"], true);
+    }
+
+    #[test]
+    fn invalid_times_are_exported_as_null() {
+        let attempt = named_attempt("Anathema", f32::NAN);
+        let json = serde_json::to_string(&ExportRow::new(&attempt)).unwrap();
+
+        let row: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(row["igt"].is_null());
+    }
+
+    #[test]
+    fn next_story_mission_completes_the_attempt() {
+        let history = play(&[
+            (Some("C1-1\\"), 100.0),
+            (Some("C1-1\\"), 900.0),
+            // Debriefing and loading of the next mission
+            (None, 0.0),
+            (Some("C2-1\\"), 0.0),
+            (Some("C2-1\\"), 10.0),
+        ]);
+
+        let attempts = history.attempts();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].mission_code, "C1-1\\");
+        assert_eq!(attempts[0].time, 900.0);
+        assert!(attempts[0].completed);
+        assert!(!attempts[1].completed);
+    }
+
+    #[test]
+    fn restarted_or_abandoned_attempts_are_not_completed() {
+        let history = play(&[
+            (Some("C1-1\\"), 900.0),
+            // Restart of the same mission
+            (Some("C1-1\\"), 10.0),
+            (None, 0.0),
+            // Switch to a mission that does not follow in the story
+            (Some("C2-3\\"), 10.0),
+        ]);
+
+        let completed: Vec<bool> = history.attempts().iter().map(|a| a.completed).collect();
+        assert_eq!(completed, [false, false, false]);
+    }
+
+    #[test]
+    fn final_mission_is_completed_in_the_menus() {
+        let last = hm2::MISSIONS.last().unwrap().code;
+        let history = play(&[(Some(last), 3000.0), (Some(last), 0.0), (None, 0.0)]);
+
+        assert_eq!(history.attempts().len(), 1);
+        assert!(history.attempts()[0].completed);
+    }
+}
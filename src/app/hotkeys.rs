@@ -0,0 +1,497 @@
+use super::history::format_date;
+
+use egui::Key;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Actions that can be triggered with a global hotkey
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+pub enum HotkeyAction {
+    ToggleOverlay,
+    ToggleCompact,
+    Split,
+    ResetTimer,
+    Marker,
+}
+
+impl HotkeyAction {
+    pub fn all_actions() -> [HotkeyAction; 5] {
+        [
+            HotkeyAction::ToggleOverlay,
+            HotkeyAction::ToggleCompact,
+            HotkeyAction::Split,
+            HotkeyAction::ResetTimer,
+            HotkeyAction::Marker,
+        ]
+    }
+
+    pub fn get_label(&self) -> &str {
+        match self {
+            HotkeyAction::ToggleOverlay => "Toggle Overlay",
+            HotkeyAction::ToggleCompact => "Compact View",
+            HotkeyAction::Split => "Split",
+            HotkeyAction::ResetTimer => "Reset Timer",
+            HotkeyAction::Marker => "Marker",
+        }
+    }
+
+    /// Explanation of actions that only apply in some situations
+    pub fn get_description(&self) -> Option<&str> {
+        match self {
+            HotkeyAction::Split => Some("Finishes the run, only in campaign mode"),
+            HotkeyAction::ResetTimer => Some("Resets the real-time timer, not the in-game time"),
+            HotkeyAction::Marker => Some("Only while a game is attached"),
+            _ => None,
+        }
+    }
+}
+
+/// Key combination of a hotkey. The modifiers must match exactly, so `F5` and `Ctrl+F5`
+/// can be bound to different actions.
+#[derive(Copy, Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Hotkey {
+    pub key: Key,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Hotkey {
+    pub fn new(key: Key, ctrl: bool, shift: bool, alt: bool) -> Self {
+        Hotkey {
+            key,
+            ctrl,
+            shift,
+            alt,
+        }
+    }
+
+    pub fn get_label(&self) -> String {
+        let mut label = String::new();
+        for (enabled, modifier) in [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+        ] {
+            if enabled {
+                label.push_str(modifier);
+            }
+        }
+        label.push_str(self.key.name());
+        label
+    }
+
+    fn is_down(&self, keyboard: &dyn KeyboardBackend) -> bool {
+        keyboard.is_down(KeyInput::Key(self.key))
+            && keyboard.is_down(KeyInput::Ctrl) == self.ctrl
+            && keyboard.is_down(KeyInput::Shift) == self.shift
+            && keyboard.is_down(KeyInput::Alt) == self.alt
+    }
+}
+
+/// User settings for the global hotkeys
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HotkeySettings {
+    pub enabled: bool,
+    pub bindings: HashMap<HotkeyAction, Hotkey>,
+    pub marker_log: String,
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        let bindings = [
+            (HotkeyAction::ToggleOverlay, Key::F1),
+            (HotkeyAction::ToggleCompact, Key::F2),
+            (HotkeyAction::Split, Key::F3),
+            (HotkeyAction::ResetTimer, Key::F4),
+            (HotkeyAction::Marker, Key::F5),
+        ]
+        .into_iter()
+        .map(|(action, key)| (action, Hotkey::new(key, true, false, false)))
+        .collect();
+
+        HotkeySettings {
+            enabled: false,
+            bindings,
+            marker_log: String::from("markers.log"),
+        }
+    }
+}
+
+/// Append a line with the current date and time to the marker log
+pub fn write_marker(path: &Path, text: &str) -> io::Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}  {}", format_date(now), text)
+}
+
+/// Keys and modifiers that can be queried from a keyboard backend
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum KeyInput {
+    Key(Key),
+    Ctrl,
+    Shift,
+    Alt,
+}
+
+/// Source of the global keyboard state, independent of the focused window
+pub trait KeyboardBackend {
+    /// Read the current keyboard state, called once before the keys are queried
+    fn refresh(&mut self) {}
+
+    fn is_down(&self, input: KeyInput) -> bool;
+}
+
+/// Detects hotkeys being pressed by polling a keyboard backend.
+/// An action triggers once when its key combination is pressed, and again only after
+/// the combination has been released.
+pub struct HotkeyListener {
+    keyboard: Box<dyn KeyboardBackend>,
+    held: Vec<HotkeyAction>,
+}
+
+impl Default for HotkeyListener {
+    fn default() -> Self {
+        HotkeyListener::new(platform_keyboard())
+    }
+}
+
+impl HotkeyListener {
+    pub fn new(keyboard: Box<dyn KeyboardBackend>) -> Self {
+        HotkeyListener {
+            keyboard,
+            held: Vec::new(),
+        }
+    }
+
+    /// Get the actions whose hotkey has been pressed since the last poll
+    pub fn poll(&mut self, bindings: &HashMap<HotkeyAction, Hotkey>) -> Vec<HotkeyAction> {
+        self.keyboard.refresh();
+
+        let mut triggered = Vec::new();
+        for action in HotkeyAction::all_actions() {
+            let down = bindings
+                .get(&action)
+                .is_some_and(|hotkey| hotkey.is_down(self.keyboard.as_ref()));
+
+            match (down, self.held.contains(&action)) {
+                (true, false) => {
+                    self.held.push(action);
+                    triggered.push(action);
+                }
+                (false, true) => self.held.retain(|held| *held != action),
+                _ => {}
+            }
+        }
+
+        triggered
+    }
+}
+
+/// Keyboard backend for platforms without global key access
+pub struct NoKeyboard;
+
+impl KeyboardBackend for NoKeyboard {
+    fn is_down(&self, _input: KeyInput) -> bool {
+        false
+    }
+}
+
+#[cfg(windows)]
+fn platform_keyboard() -> Box<dyn KeyboardBackend> {
+    Box::new(windows::WindowsKeyboard::default())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_keyboard() -> Box<dyn KeyboardBackend> {
+    Box::new(x11::X11Keyboard::default())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn platform_keyboard() -> Box<dyn KeyboardBackend> {
+    Box::new(NoKeyboard)
+}
+
+/// Global key state from the Win32 API
+#[cfg(windows)]
+mod windows {
+    use super::{KeyInput, KeyboardBackend};
+    use egui::Key;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VK_CONTROL, VK_MENU, VK_SHIFT,
+    };
+
+    /// Keyboard backend that reads all virtual keys once per poll
+    pub struct WindowsKeyboard {
+        keys: [bool; 256],
+    }
+
+    impl Default for WindowsKeyboard {
+        fn default() -> Self {
+            WindowsKeyboard { keys: [false; 256] }
+        }
+    }
+
+    impl KeyboardBackend for WindowsKeyboard {
+        fn refresh(&mut self) {
+            for (virtual_key, down) in self.keys.iter_mut().enumerate() {
+                // The most significant bit is set while the key is down, and the least
+                // significant bit if it was pressed since the last call, so that short
+                // presses between two polls are not missed
+                let state = unsafe { GetAsyncKeyState(virtual_key as i32) };
+                *down = state < 0 || state & 1 != 0;
+            }
+        }
+
+        fn is_down(&self, input: KeyInput) -> bool {
+            let virtual_key = match input {
+                KeyInput::Key(key) => match virtual_key(key) {
+                    Some(virtual_key) => virtual_key,
+                    None => return false,
+                },
+                KeyInput::Ctrl => VK_CONTROL as i32,
+                KeyInput::Shift => VK_SHIFT as i32,
+                KeyInput::Alt => VK_MENU as i32,
+            };
+
+            self.keys[virtual_key as usize]
+        }
+    }
+
+    /// Windows virtual key code of a key
+    fn virtual_key(key: Key) -> Option<i32> {
+        let name = key.name();
+
+        // Letters and digits use their ASCII code
+        if let [c] = name.as_bytes() {
+            if c.is_ascii_alphanumeric() {
+                return Some(c.to_ascii_uppercase() as i32);
+            }
+        }
+
+        // Function keys F1-F24 are consecutive
+        if let Some(number) = name.strip_prefix('F').and_then(|n| n.parse::<i32>().ok()) {
+            return (1..=24).contains(&number).then_some(0x6F + number);
+        }
+
+        let code = match key {
+            Key::Space => 0x20,
+            Key::Enter => 0x0D,
+            Key::Tab => 0x09,
+            Key::Escape => 0x1B,
+            Key::Backspace => 0x08,
+            Key::Insert => 0x2D,
+            Key::Delete => 0x2E,
+            Key::Home => 0x24,
+            Key::End => 0x23,
+            Key::PageUp => 0x21,
+            Key::PageDown => 0x22,
+            Key::ArrowLeft => 0x25,
+            Key::ArrowUp => 0x26,
+            Key::ArrowRight => 0x27,
+            Key::ArrowDown => 0x28,
+            _ => return None,
+        };
+        Some(code)
+    }
+}
+
+/// Global key state from the X11 keymap, which is also available under XWayland
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::{KeyInput, KeyboardBackend};
+    use egui::Key;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::ConnectionExt;
+    use x11rb::rust_connection::RustConnection;
+
+    /// Keyboard backend that polls the X server. The connection is opened on first use,
+    /// and all keys read as released if no X server is available.
+    #[derive(Default)]
+    pub struct X11Keyboard {
+        connection: Option<XConnection>,
+        connect_failed: bool,
+        keymap: [u8; 32],
+    }
+
+    struct XConnection {
+        conn: RustConnection,
+        min_keycode: u8,
+        keysyms_per_keycode: usize,
+        keysyms: Vec<u32>,
+    }
+
+    impl KeyboardBackend for X11Keyboard {
+        fn refresh(&mut self) {
+            if self.connection.is_none() && !self.connect_failed {
+                self.connection = connect();
+                self.connect_failed = self.connection.is_none();
+            }
+
+            self.keymap = self
+                .connection
+                .as_ref()
+                .and_then(|c| c.conn.query_keymap().ok()?.reply().ok())
+                .map_or([0; 32], |reply| reply.keys);
+        }
+
+        fn is_down(&self, input: KeyInput) -> bool {
+            let Some(connection) = self.connection.as_ref() else {
+                return false;
+            };
+
+            keysyms(input).iter().any(|keysym| {
+                connection
+                    .keycodes(*keysym)
+                    .any(|keycode| self.keymap[keycode as usize / 8] & (1 << (keycode % 8)) != 0)
+            })
+        }
+    }
+
+    impl XConnection {
+        /// Keycodes that produce a keysym
+        fn keycodes(&self, keysym: u32) -> impl Iterator<Item = u8> + '_ {
+            self.keysyms
+                .chunks(self.keysyms_per_keycode.max(1))
+                .enumerate()
+                .filter(move |(_, syms)| syms.contains(&keysym))
+                .map(|(i, _)| self.min_keycode.saturating_add(i as u8))
+        }
+    }
+
+    fn connect() -> Option<XConnection> {
+        let (conn, _) = RustConnection::connect(None).ok()?;
+        let setup = conn.setup();
+        let (min_keycode, max_keycode) = (setup.min_keycode, setup.max_keycode);
+
+        let mapping = conn
+            .get_keyboard_mapping(min_keycode, max_keycode - min_keycode + 1)
+            .ok()?
+            .reply()
+            .ok()?;
+
+        Some(XConnection {
+            conn,
+            min_keycode,
+            keysyms_per_keycode: mapping.keysyms_per_keycode as usize,
+            keysyms: mapping.keysyms,
+        })
+    }
+
+    /// X11 keysyms of a key, including both the left and right modifier keys
+    fn keysyms(input: KeyInput) -> Vec<u32> {
+        let key = match input {
+            KeyInput::Ctrl => return vec![0xFFE3, 0xFFE4],
+            KeyInput::Shift => return vec![0xFFE1, 0xFFE2],
+            KeyInput::Alt => return vec![0xFFE9, 0xFFEA],
+            KeyInput::Key(key) => key,
+        };
+
+        // Letters and digits use their ASCII code, with lower case letters
+        if let [c] = key.name().as_bytes() {
+            if c.is_ascii_alphanumeric() {
+                return vec![c.to_ascii_lowercase() as u32];
+            }
+        }
+
+        // Function keys F1-F35 are consecutive
+        if let Some(number) = key
+            .name()
+            .strip_prefix('F')
+            .and_then(|n| n.parse::<u32>().ok())
+        {
+            return vec![0xFFBD + number];
+        }
+
+        let keysym = match key {
+            Key::Space => 0x20,
+            Key::Enter => 0xFF0D,
+            Key::Tab => 0xFF09,
+            Key::Escape => 0xFF1B,
+            Key::Backspace => 0xFF08,
+            Key::Insert => 0xFF63,
+            Key::Delete => 0xFFFF,
+            Key::Home => 0xFF50,
+            Key::End => 0xFF57,
+            Key::PageUp => 0xFF55,
+            Key::PageDown => 0xFF56,
+            Key::ArrowLeft => 0xFF51,
+            Key::ArrowUp => 0xFF52,
+            Key::ArrowRight => 0xFF53,
+            Key::ArrowDown => 0xFF54,
+            _ => return Vec::new(),
+        };
+        vec![keysym]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Keyboard backend with keys set by the test
+    struct FakeKeyboard(Rc<RefCell<Vec<KeyInput>>>);
+
+    impl KeyboardBackend for FakeKeyboard {
+        fn is_down(&self, input: KeyInput) -> bool {
+            self.0.borrow().contains(&input)
+        }
+    }
+
+    fn listener() -> (HotkeyListener, Rc<RefCell<Vec<KeyInput>>>) {
+        let keys = Rc::new(RefCell::new(Vec::new()));
+        let listener = HotkeyListener::new(Box::new(FakeKeyboard(keys.clone())));
+        (listener, keys)
+    }
+
+    #[test]
+    fn triggers_once_per_press() {
+        let (mut listener, keys) = listener();
+        let bindings = HotkeySettings::default().bindings;
+
+        keys.replace(vec![KeyInput::Ctrl, KeyInput::Key(Key::F3)]);
+        assert_eq!(listener.poll(&bindings), vec![HotkeyAction::Split]);
+        assert!(listener.poll(&bindings).is_empty());
+
+        keys.replace(vec![KeyInput::Ctrl]);
+        assert!(listener.poll(&bindings).is_empty());
+
+        keys.replace(vec![KeyInput::Ctrl, KeyInput::Key(Key::F3)]);
+        assert_eq!(listener.poll(&bindings), vec![HotkeyAction::Split]);
+    }
+
+    #[test]
+    fn modifiers_must_match() {
+        let (mut listener, keys) = listener();
+        let mut bindings = HashMap::new();
+        bindings.insert(
+            HotkeyAction::Marker,
+            Hotkey::new(Key::M, false, false, false),
+        );
+        bindings.insert(HotkeyAction::Split, Hotkey::new(Key::M, false, true, false));
+
+        keys.replace(vec![KeyInput::Key(Key::M)]);
+        assert_eq!(listener.poll(&bindings), vec![HotkeyAction::Marker]);
+
+        keys.replace(vec![KeyInput::Shift, KeyInput::Key(Key::M)]);
+        assert_eq!(listener.poll(&bindings), vec![HotkeyAction::Split]);
+    }
+
+    #[test]
+    fn unbound_actions_never_trigger() {
+        let (mut listener, keys) = listener();
+
+        keys.replace(vec![KeyInput::Ctrl, KeyInput::Key(Key::F1)]);
+        assert!(listener.poll(&HashMap::new()).is_empty());
+    }
+}
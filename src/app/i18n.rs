@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use tracing::warn;
+
+// Index of the selected language in `Language::all_languages`
+static LANGUAGE: AtomicUsize = AtomicUsize::new(0);
+
+/// Languages with a shipped message catalog
+#[derive(Copy, Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub fn all_languages() -> [Language; 2] {
+        [Language::English, Language::German]
+    }
+
+    /// Name of the language in the language itself
+    pub fn get_label(&self) -> &str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    fn catalog_source(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../assets/locales/en.toml"),
+            Language::German => include_str!("../../assets/locales/de.toml"),
+        }
+    }
+
+    fn index(&self) -> usize {
+        Language::all_languages()
+            .iter()
+            .position(|language| language == self)
+            .unwrap_or(0)
+    }
+}
+
+/// Translations of a single language. UI strings are keyed by their English text, so
+/// missing translations fall back to English. Mission names are keyed by mission code.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Catalog {
+    ui: HashMap<String, String>,
+    missions: HashMap<String, String>,
+}
+
+/// Parsed catalogs of all languages, in the order of `Language::all_languages`
+fn catalogs() -> &'static Vec<Catalog> {
+    static CATALOGS: OnceLock<Vec<Catalog>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        Language::all_languages()
+            .iter()
+            .map(|language| {
+                // The catalogs are checked by the tests, a broken catalog falls back to English
+                toml::from_str(language.catalog_source()).unwrap_or_else(|err| {
+                    warn!(language = language.get_label(), %err, "Failed to parse the catalog");
+                    Catalog::default()
+                })
+            })
+            .collect()
+    })
+}
+
+/// Select the language of all translated text
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language.index(), Ordering::Relaxed);
+}
+
+fn current_catalog() -> &'static Catalog {
+    &catalogs()[LANGUAGE.load(Ordering::Relaxed)]
+}
+
+/// Translate a UI string given in English
+pub fn tr(text: &str) -> &str {
+    current_catalog().ui.get(text).map_or(text, |t| t.as_str())
+}
+
+/// Translate a UI string given in English and fill its `{}` placeholders in order
+pub fn tr_args(text: &str, args: &[&dyn Display]) -> String {
+    let mut parts = tr(text).split("{}");
+    let mut result = parts.next().unwrap_or_default().to_string();
+
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            result.push_str(&arg.to_string());
+        }
+        result.push_str(part);
+    }
+
+    result
+}
+
+/// Name of a mission in the selected language, falling back to the English catalog
+/// and then to the name given by the backend
+pub fn mission_name<'a>(code: Option<&str>, name: &'a str) -> &'a str {
+    let Some(code) = code else {
+        return name;
+    };
+
+    [current_catalog(), &catalogs()[Language::English.index()]]
+        .into_iter()
+        .find_map(|catalog| catalog.missions.get(code))
+        .map_or(name, |name| name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::backends::missions;
+    use crate::app::overlay::{OverlayAnchor, OverlayLayout};
+    use crate::app::{hotkeys::HotkeyAction, layout::LayoutPreset, time_format::TimeFormat};
+    use crate::{Difficulty, STAT_NAMES};
+    use std::{fs, path::Path};
+
+    /// English texts given to `tr` and `tr_args` as string literals in the source files
+    fn literal_keys(dir: &Path, keys: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                literal_keys(&path, keys);
+                continue;
+            }
+            if path.extension().map_or(true, |ext| ext != "rs") {
+                continue;
+            }
+
+            let source = fs::read_to_string(&path).unwrap();
+            for call in ["tr(", "tr_args("] {
+                for (start, _) in source.match_indices(call) {
+                    // Skip other functions ending in the same name, e.g. `str(`, and the
+                    // names of the functions in this test
+                    let before = source[..start].chars().next_back();
+                    if before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '"') {
+                        continue;
+                    }
+
+                    let rest = source[start + call.len()..].trim_start();
+                    if let Some(literal) = rest.strip_prefix('"') {
+                        keys.push(unescape(literal));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read a Rust string literal up to its closing quote
+    fn unescape(literal: &str) -> String {
+        let mut text = String::new();
+        let mut chars = literal.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some(c) => text.push(c),
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        text
+    }
+
+    /// Labels of settings and missions that are translated at runtime
+    fn label_keys() -> Vec<String> {
+        let mut keys: Vec<String> = STAT_NAMES.map(String::from).to_vec();
+        keys.extend(Difficulty::all_difficulties().map(|d| d.get_label().to_string()));
+        keys.extend(TimeFormat::all_formats().map(|f| f.get_label().to_string()));
+        keys.extend(LayoutPreset::all_presets().map(|p| p.get_label().to_string()));
+        keys.extend(OverlayLayout::all_layouts().map(|l| l.get_label().to_string()));
+        keys.extend(OverlayAnchor::all_anchors().map(|a| a.get_label().to_string()));
+        keys.extend(HotkeyAction::all_actions().map(|a| a.get_label().to_string()));
+        keys.extend(
+            HotkeyAction::all_actions()
+                .iter()
+                .filter_map(|a| a.get_description().map(str::to_string)),
+        );
+        for mission in missions::catalogs().iter().flat_map(|c| c.missions()) {
+            keys.extend([mission.location, mission.sa_notes].map(String::from));
+        }
+
+        keys.retain(|key| !key.is_empty());
+        keys
+    }
+
+    #[test]
+    fn all_catalogs_parse() {
+        for language in Language::all_languages() {
+            let catalog = toml::from_str::<Catalog>(language.catalog_source());
+            assert!(catalog.is_ok(), "{language:?}: {:?}", catalog.err());
+        }
+    }
+
+    #[test]
+    fn all_catalogs_translate_every_key() {
+        let mut keys = label_keys();
+        literal_keys(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut keys,
+        );
+        assert!(keys.iter().any(|key| key == "Settings"));
+
+        // English is the source language, so its catalog only overrides texts
+        for language in Language::all_languages() {
+            if language == Language::English {
+                continue;
+            }
+
+            let catalog: Catalog = toml::from_str(language.catalog_source()).unwrap();
+            let missing: Vec<&String> = keys
+                .iter()
+                .filter(|key| !catalog.ui.contains_key(*key))
+                .collect();
+            assert!(missing.is_empty(), "{language:?} is missing {missing:?}");
+
+            for mission in missions::catalogs().iter().flat_map(|c| c.missions()) {
+                assert!(
+                    catalog.missions.contains_key(mission.code),
+                    "{language:?} is missing the mission {}",
+                    mission.code
+                );
+            }
+        }
+    }
+}
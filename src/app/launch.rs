@@ -0,0 +1,178 @@
+use super::logging::LogLevel;
+use super::system_access::Game;
+
+use std::path::PathBuf;
+
+/// Name of the application, which also selects the directory of the stored settings
+pub const APP_NAME: &str = "Hitman StatTracker";
+
+// Default minimum refresh rates of the app in Hz, while a game is running and while waiting for one
+// Note: Refresh rate will increase if the cursor is moved around while the window is in focus
+pub const RUNNING_REFRESH_RATE: usize = 30;
+pub const WAITING_REFRESH_RATE: usize = 1;
+
+/// Usage text printed by `--help`
+pub const USAGE: &str = "\
+Usage: StatTracker [OPTIONS]
+
+Options:
+  --game <hm2|hmc>             Only attach to the given game
+  --pid <pid>                  Only attach to the game process with the given ID
+  --config <path>              Load and save the settings in the given file
+  --overlay                    Start with the game overlay enabled
+  --compact                    Start with the compact window layout
+  --refresh-rate <hz>          Refresh rate while a game is running (default 30)
+  --waiting-refresh-rate <hz>  Refresh rate while waiting for a game (default 1)
+  --record <path>              Record all data read from the game to a file
+  --log-level <level>          Detail of the log file: off, error, warn, info, debug or trace
+  -h, --help                   Print this help
+
+The overlay, layout and log level given here only apply to this session and are not saved.
+";
+
+/// Options given on the command line when launching the tracker, e.g. by a game launcher
+pub struct LaunchOptions {
+    pub game: Option<Game>,
+    pub pid: Option<u32>,
+    pub config: Option<PathBuf>,
+    pub overlay: bool,
+    pub compact: bool,
+    pub running_refresh_rate: usize,
+    pub waiting_refresh_rate: usize,
+    pub record: Option<PathBuf>,
+    pub log_level: Option<LogLevel>,
+    pub help: bool,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        LaunchOptions {
+            game: None,
+            pid: None,
+            config: None,
+            overlay: false,
+            compact: false,
+            running_refresh_rate: RUNNING_REFRESH_RATE,
+            waiting_refresh_rate: WAITING_REFRESH_RATE,
+            record: None,
+            log_level: None,
+            help: false,
+        }
+    }
+}
+
+impl LaunchOptions {
+    /// Parse the command line arguments, excluding the executable name.
+    /// Values are given either as the next argument or as `--option=value`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<LaunchOptions, String> {
+        let mut options = LaunchOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+
+            // Take the value of the current option
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("Missing value for {flag}"))
+            };
+
+            match flag.as_str() {
+                "--game" => {
+                    let name = value()?;
+                    let game = Game::all_games()
+                        .into_iter()
+                        .find(|game| game.get_label().eq_ignore_ascii_case(&name))
+                        .ok_or_else(|| format!("Unknown game: {name}"))?;
+                    options.game = Some(game);
+                }
+                "--pid" => options.pid = Some(parse_number(&flag, &value()?)?),
+                "--config" => options.config = Some(PathBuf::from(value()?)),
+                "--overlay" => options.overlay = true,
+                "--compact" => options.compact = true,
+                "--refresh-rate" => options.running_refresh_rate = parse_rate(&flag, &value()?)?,
+                "--waiting-refresh-rate" => {
+                    options.waiting_refresh_rate = parse_rate(&flag, &value()?)?
+                }
+                "--record" => options.record = Some(PathBuf::from(value()?)),
+                "--log-level" => {
+                    let name = value()?;
+                    let level = LogLevel::all_levels()
+                        .into_iter()
+                        .find(|level| level.get_label().eq_ignore_ascii_case(&name))
+                        .ok_or_else(|| format!("Unknown log level: {name}"))?;
+                    options.log_level = Some(level);
+                }
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("Unknown option: {flag}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {flag}: {value}"))
+}
+
+/// Refresh rates are used as divisors, so they have to be at least 1 Hz
+fn parse_rate(flag: &str, value: &str) -> Result<usize, String> {
+    match parse_number(flag, value)? {
+        0 => Err(format!("{flag} must be at least 1")),
+        rate => Ok(rate),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+        LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn values_follow_the_option_or_an_equals_sign() {
+        let options = parse(&["--game", "HMC", "--pid=1234", "--refresh-rate", "60"]).unwrap();
+        assert_eq!(options.game, Some(Game::HmC));
+        assert_eq!(options.pid, Some(1234));
+        assert_eq!(options.running_refresh_rate, 60);
+
+        let options = parse(&["--config=C:\\a=b.ron", "--log-level=debug", "--overlay"]).unwrap();
+        assert_eq!(options.config, Some(PathBuf::from("C:\\a=b.ron")));
+        assert_eq!(options.log_level, Some(LogLevel::Debug));
+        assert!(options.overlay);
+        assert!(!options.compact);
+    }
+
+    #[test]
+    fn no_options_keep_the_defaults() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options.game, None);
+        assert_eq!(options.running_refresh_rate, RUNNING_REFRESH_RATE);
+        assert_eq!(options.waiting_refresh_rate, WAITING_REFRESH_RATE);
+        assert!(!options.help);
+    }
+
+    #[test]
+    fn invalid_options_are_rejected() {
+        let error = |args: &[&str]| parse(args).err().unwrap();
+
+        assert_eq!(error(&["--pid"]), "Missing value for --pid");
+        assert_eq!(error(&["--fullscreen"]), "Unknown option: --fullscreen");
+        assert_eq!(error(&["--game", "hm3"]), "Unknown game: hm3");
+        assert_eq!(error(&["--pid", "abc"]), "Invalid value for --pid: abc");
+        assert_eq!(
+            error(&["--waiting-refresh-rate=0"]),
+            "--waiting-refresh-rate must be at least 1"
+        );
+    }
+}
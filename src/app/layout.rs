@@ -0,0 +1,113 @@
+use egui::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Arrangement of the main window while a game is running
+#[derive(Copy, Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub enum LayoutPreset {
+    Full,
+    Compact,
+    StatsOnly,
+    TimerOnly,
+}
+
+impl LayoutPreset {
+    pub fn all_presets() -> [LayoutPreset; 4] {
+        [
+            LayoutPreset::Full,
+            LayoutPreset::Compact,
+            LayoutPreset::StatsOnly,
+            LayoutPreset::TimerOnly,
+        ]
+    }
+
+    pub fn get_label(&self) -> &str {
+        match self {
+            LayoutPreset::Full => "Full",
+            LayoutPreset::Compact => "Compact",
+            LayoutPreset::StatsOnly => "Stats Only",
+            LayoutPreset::TimerOnly => "Timer Only",
+        }
+    }
+
+    /// Window size of the layout with the text at its normal size
+    pub fn base_size(&self) -> Vec2 {
+        match self {
+            LayoutPreset::Full => Vec2::new(270.0, 330.0),
+            LayoutPreset::Compact => Vec2::new(270.0, 50.0),
+            LayoutPreset::StatsOnly => Vec2::new(270.0, 220.0),
+            LayoutPreset::TimerOnly => Vec2::new(270.0, 90.0),
+        }
+    }
+
+    pub fn show_heading(&self) -> bool {
+        *self == LayoutPreset::Full
+    }
+
+    pub fn show_stats(&self) -> bool {
+        matches!(self, LayoutPreset::Full | LayoutPreset::StatsOnly)
+    }
+
+    pub fn show_rating(&self) -> bool {
+        *self != LayoutPreset::TimerOnly
+    }
+}
+
+/// User settings for the main window layout
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LayoutSettings {
+    pub preset: LayoutPreset,
+    /// Order of the stats as indices into `MissionStats::to_array`
+    pub stat_order: [usize; 8],
+    pub show_stats: [bool; 8],
+    pub scale_text: bool,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        LayoutSettings {
+            preset: LayoutPreset::Full,
+            stat_order: [0, 1, 2, 3, 4, 5, 6, 7],
+            show_stats: [true; 8],
+            scale_text: true,
+        }
+    }
+}
+
+impl LayoutSettings {
+    /// Indices of the visible stats in the selected order
+    pub fn visible_stats(&self) -> impl Iterator<Item = usize> + '_ {
+        self.stat_order
+            .iter()
+            .copied()
+            .filter(|i| self.show_stats.get(*i).copied().unwrap_or(false))
+    }
+
+    /// Swap the stat at the given position in the order with the one before it
+    pub fn move_stat_up(&mut self, position: usize) {
+        if position > 0 && position < self.stat_order.len() {
+            self.stat_order.swap(position - 1, position);
+        }
+    }
+
+    /// Switch between the compact and the full layout
+    pub fn toggle_compact(&mut self) {
+        self.preset = if self.preset == LayoutPreset::Compact {
+            LayoutPreset::Full
+        } else {
+            LayoutPreset::Compact
+        };
+    }
+
+    /// Factor to scale the text by to fit a window of the given size
+    pub fn text_scale(&self, window_size: Vec2) -> f32 {
+        if !self.scale_text {
+            return 1.0;
+        }
+
+        let base = self.preset.base_size();
+        (window_size.x / base.x)
+            .min(window_size.y / base.y)
+            .clamp(0.5, 3.0)
+    }
+}
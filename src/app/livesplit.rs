@@ -0,0 +1,357 @@
+use super::backends::{hm2, hmc, missions::catalogs};
+use super::{campaign::CampaignRun, time_format::TICKS_PER_SECOND};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::{fs, io, path::Path};
+
+/// Comparison times read from a LiveSplit split file in ticks, by mission code
+#[derive(Default)]
+pub struct ImportedSplits {
+    pub personal_best: HashMap<String, f32>,
+    pub best_segments: HashMap<String, f32>,
+    pub unmatched: Vec<String>,
+}
+
+/// Import the personal best and best segment times of a LiveSplit `.lss` file.
+/// Segments are mapped to missions by either their mission name or mission code.
+pub fn import_lss(path: &Path) -> io::Result<ImportedSplits> {
+    parse_lss(&fs::read_to_string(path)?)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn parse_lss(xml: &str) -> Result<ImportedSplits, quick_xml::DeError> {
+    let run: LssRun = quick_xml::de::from_str(xml)?;
+
+    let mut imported = ImportedSplits::default();
+    let missions = game_missions(&run.game_name);
+
+    // Personal best split times are cumulative, so the previous split is subtracted
+    let mut previous_split = Some(0.0);
+
+    for segment in run.segments.segment {
+        let pb_split = segment
+            .split_times
+            .split_time
+            .iter()
+            .find(|split| split.name == "Personal Best")
+            .and_then(|split| game_or_real_time(&split.game_time, &split.real_time));
+
+        let pb_segment = pb_split.zip(previous_split).map(|(pb, prev)| pb - prev);
+        previous_split = pb_split;
+
+        let name = segment.name.trim();
+        let code = missions
+            .iter()
+            .find(|(code, mission)| {
+                code.eq_ignore_ascii_case(name) || mission.eq_ignore_ascii_case(name)
+            })
+            .map(|(code, _)| code);
+
+        match code {
+            Some(code) => {
+                if let Some(time) = pb_segment {
+                    imported
+                        .personal_best
+                        .insert(code.clone(), time * TICKS_PER_SECOND);
+                }
+                if let Some(time) = segment.best_segment_time.seconds() {
+                    imported
+                        .best_segments
+                        .insert(code.clone(), time * TICKS_PER_SECOND);
+                }
+            }
+            None => imported.unmatched.push(name.to_string()),
+        }
+    }
+
+    Ok(imported)
+}
+
+/// Export the splits of a campaign run as a LiveSplit `.lss` file with the run as the
+/// personal best and the best times as best segments. Times are written as game time.
+pub fn export_lss(
+    path: &Path,
+    run: &CampaignRun,
+    best_times: &HashMap<String, f32>,
+) -> io::Result<()> {
+    fs::write(path, lss_xml(run, best_times)?)
+}
+
+fn lss_xml(run: &CampaignRun, best_times: &HashMap<String, f32>) -> io::Result<String> {
+    // Use the game of the first completed mission
+    let (game_name, missions) = [
+        (hm2::GAME_NAME, hm2::missions().mission_list()),
+        (hmc::GAME_NAME, hmc::missions().mission_list()),
+    ]
+    .into_iter()
+    .find(|(_, missions)| {
+        missions
+            .iter()
+            .any(|(code, _)| run.get_split(code).is_some())
+    })
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No missions in the run"))?;
+
+    // The run starts at its first mission, e.g. after skipping the training.
+    // Split times are cumulative and stop at the first mission without a time.
+    let mut total = Some(0.0);
+
+    let segment = missions
+        .into_iter()
+        .skip_while(|(code, _)| run.get_split(code).is_none())
+        .map(|(code, mission)| {
+            total = total
+                .zip(run.get_split(&code))
+                .map(|(total, (split, _))| total + split.time / TICKS_PER_SECOND);
+
+            LssSegment {
+                name: mission.clone(),
+                icon: String::new(),
+                split_times: LssSplitTimes {
+                    split_time: vec![LssSplitTime {
+                        name: String::from("Personal Best"),
+                        real_time: None,
+                        game_time: total.map(format_time),
+                    }],
+                },
+                best_segment_time: LssTime::from_game_time(
+                    best_times.get(&code).map(|time| time / TICKS_PER_SECOND),
+                ),
+                segment_history: String::new(),
+            }
+        })
+        .collect();
+
+    let run = LssRun {
+        version: String::from("1.7.0"),
+        game_icon: String::new(),
+        game_name: game_name.to_string(),
+        category_name: String::from("Any%"),
+        offset: String::from("00:00:00"),
+        attempt_count: 0,
+        attempt_history: String::new(),
+        segments: LssSegments { segment },
+        auto_splitter_settings: String::new(),
+    };
+
+    let xml = quick_xml::se::to_string(&run)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}",
+        xml
+    ))
+}
+
+/// Missions matching the game name of a split file, or the missions of all games
+fn game_missions(game_name: &str) -> Vec<(String, String)> {
+    match game_name {
+        name if name.eq_ignore_ascii_case(hm2::GAME_NAME) => hm2::missions().mission_list(),
+        name if name.eq_ignore_ascii_case(hmc::GAME_NAME) => hmc::missions().mission_list(),
+        _ => catalogs()
+            .into_iter()
+            .flat_map(|catalog| catalog.mission_list())
+            .collect(),
+    }
+}
+
+/// Parse a LiveSplit time of the form `[d.]hh:mm:ss[.fffffff]` to seconds
+fn parse_time(time: &str) -> Option<f32> {
+    let mut parts = time.trim().rsplit(':');
+    let seconds = parts.next()?.parse::<f32>().ok()?;
+    let minutes = parts.next().map_or(Some(0.0), |m| m.parse::<f32>().ok())?;
+
+    let hours = match parts.next() {
+        Some(hours) => match hours.split_once('.') {
+            Some((days, hours)) => days.parse::<f32>().ok()? * 24.0 + hours.parse::<f32>().ok()?,
+            None => hours.parse::<f32>().ok()?,
+        },
+        None => 0.0,
+    };
+
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Format seconds as a LiveSplit time
+fn format_time(seconds: f32) -> String {
+    let ticks = (seconds.max(0.0) as f64 * 10_000_000.0).round() as u64;
+    let total_seconds = ticks / 10_000_000;
+
+    format!(
+        "{:0>2}:{:0>2}:{:0>2}.{:0>7}",
+        total_seconds / 3600,
+        (total_seconds / 60) % 60,
+        total_seconds % 60,
+        ticks % 10_000_000
+    )
+}
+
+// Subset of the LiveSplit split file format (`.lss`) used by the tracker
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "Run")]
+struct LssRun {
+    #[serde(rename = "@version", default)]
+    version: String,
+    #[serde(rename = "GameIcon", skip_deserializing)]
+    game_icon: String,
+    #[serde(rename = "GameName", default)]
+    game_name: String,
+    #[serde(rename = "CategoryName", default)]
+    category_name: String,
+    #[serde(rename = "Offset", skip_deserializing)]
+    offset: String,
+    #[serde(rename = "AttemptCount", skip_deserializing)]
+    attempt_count: u32,
+    #[serde(rename = "AttemptHistory", skip_deserializing)]
+    attempt_history: String,
+    #[serde(rename = "Segments", default)]
+    segments: LssSegments,
+    #[serde(rename = "AutoSplitterSettings", skip_deserializing)]
+    auto_splitter_settings: String,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct LssSegments {
+    #[serde(rename = "Segment", default)]
+    segment: Vec<LssSegment>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct LssSegment {
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "Icon", skip_deserializing)]
+    icon: String,
+    #[serde(rename = "SplitTimes", default)]
+    split_times: LssSplitTimes,
+    #[serde(rename = "BestSegmentTime", default)]
+    best_segment_time: LssTime,
+    #[serde(rename = "SegmentHistory", skip_deserializing)]
+    segment_history: String,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct LssSplitTimes {
+    #[serde(rename = "SplitTime", default)]
+    split_time: Vec<LssSplitTime>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct LssSplitTime {
+    #[serde(rename = "@name", default)]
+    name: String,
+    #[serde(rename = "RealTime", skip_serializing_if = "Option::is_none")]
+    real_time: Option<String>,
+    #[serde(rename = "GameTime", skip_serializing_if = "Option::is_none")]
+    game_time: Option<String>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct LssTime {
+    #[serde(rename = "RealTime", skip_serializing_if = "Option::is_none")]
+    real_time: Option<String>,
+    #[serde(rename = "GameTime", skip_serializing_if = "Option::is_none")]
+    game_time: Option<String>,
+}
+
+impl LssTime {
+    fn from_game_time(seconds: Option<f32>) -> Self {
+        LssTime {
+            real_time: None,
+            game_time: seconds.map(format_time),
+        }
+    }
+
+    fn seconds(&self) -> Option<f32> {
+        game_or_real_time(&self.game_time, &self.real_time)
+    }
+}
+
+/// Game time of a split, falling back to real time if no game time is recorded
+fn game_or_real_time(game_time: &Option<String>, real_time: &Option<String>) -> Option<f32> {
+    game_time
+        .as_deref()
+        .and_then(parse_time)
+        .or_else(|| real_time.as_deref().and_then(parse_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_fixtures::game_data;
+
+    #[test]
+    fn parses_livesplit_times() {
+        assert_eq!(parse_time("00:01:30.5000000"), Some(90.5));
+        assert_eq!(parse_time("1:02:03"), Some(3723.0));
+        assert_eq!(parse_time("1.02:00:00"), Some(93600.0));
+        assert_eq!(parse_time("42.25"), Some(42.25));
+        assert_eq!(parse_time("00:xx:10"), None);
+        assert_eq!(parse_time(""), None);
+    }
+
+    #[test]
+    fn formats_livesplit_times() {
+        assert_eq!(format_time(0.0), "00:00:00.0000000");
+        assert_eq!(format_time(90.5), "00:01:30.5000000");
+        assert_eq!(format_time(3723.25), "01:02:03.2500000");
+        assert_eq!(format_time(-5.0), "00:00:00.0000000");
+        assert_eq!(parse_time(&format_time(754.125)), Some(754.125));
+    }
+
+    #[test]
+    fn imports_segments_by_name_or_code() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Run version="1.7.0">
+              <GameName>Hitman 2: Silent Assassin</GameName>
+              <Segments>
+                <Segment>
+                  <Name>Anathema</Name>
+                  <SplitTimes>
+                    <SplitTime name="Personal Best"><RealTime>00:02:10</RealTime><GameTime>00:02:00</GameTime></SplitTime>
+                  </SplitTimes>
+                  <BestSegmentTime><GameTime>00:01:50</GameTime></BestSegmentTime>
+                </Segment>
+                <Segment>
+                  <Name>c2-1\</Name>
+                  <SplitTimes>
+                    <SplitTime name="Personal Best"><RealTime>00:05:00</RealTime></SplitTime>
+                  </SplitTimes>
+                  <BestSegmentTime />
+                </Segment>
+                <Segment>
+                  <Name>Reset</Name>
+                  <SplitTimes />
+                  <BestSegmentTime />
+                </Segment>
+              </Segments>
+            </Run>"#;
+        let imported = parse_lss(xml).unwrap();
+
+        assert_eq!(imported.personal_best.len(), 2);
+        assert_eq!(imported.personal_best["C1-1\\"], 120.0 * TICKS_PER_SECOND);
+        assert_eq!(imported.personal_best["C2-1\\"], 180.0 * TICKS_PER_SECOND);
+        assert_eq!(imported.best_segments["C1-1\\"], 110.0 * TICKS_PER_SECOND);
+        assert_eq!(imported.best_segments.len(), 1);
+        assert_eq!(imported.unmatched, ["Reset"]);
+    }
+
+    #[test]
+    fn exported_runs_import_again() {
+        let missions = hm2::missions();
+        let mut best_times = HashMap::new();
+        let mut run = CampaignRun::default();
+        run.update(&game_data("C1-1\\", 7200.0), missions, &mut best_times);
+        run.update(&game_data("C2-1\\", 9000.0), missions, &mut best_times);
+        run.finish(&mut best_times);
+
+        let imported = parse_lss(&lss_xml(&run, &best_times).unwrap()).unwrap();
+
+        assert_eq!(imported.personal_best.len(), 2);
+        assert_eq!(imported.personal_best["C1-1\\"], 7200.0);
+        assert_eq!(imported.personal_best["C2-1\\"], 9000.0);
+        assert_eq!(imported.best_segments, best_times);
+        assert!(imported.unmatched.is_empty());
+    }
+}
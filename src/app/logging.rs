@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{fmt, prelude::*, reload, Registry};
+
+/// Name of the log files, each day is written to its own file, e.g. `stattracker.2024-06-01.log`
+pub const LOG_FILE_PREFIX: &str = "stattracker";
+
+// Number of daily log files that are kept, older files are deleted
+const LOG_FILES: usize = 3;
+
+// Handle to change the level of the log file while the app is running
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// Most detailed level of the messages written to the log file
+#[derive(Copy, Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn all_levels() -> [LogLevel; 6] {
+        [
+            LogLevel::Off,
+            LogLevel::Error,
+            LogLevel::Warn,
+            LogLevel::Info,
+            LogLevel::Debug,
+            LogLevel::Trace,
+        ]
+    }
+
+    pub fn get_label(&self) -> &str {
+        match self {
+            LogLevel::Off => "Off",
+            LogLevel::Error => "Error",
+            LogLevel::Warn => "Warn",
+            LogLevel::Info => "Info",
+            LogLevel::Debug => "Debug",
+            LogLevel::Trace => "Trace",
+        }
+    }
+
+    fn filter(&self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Select the level of the messages written to the log file
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        // Only fails if the subscriber is gone, in which case nothing is logged anyway
+        let _ = handle.modify(|filter| *filter = level.filter());
+    }
+}
+
+/// Start writing the log messages of the whole application to daily log files in the directory
+pub fn init(dir: &Path, level: LogLevel) -> io::Result<()> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(LOG_FILES)
+        .build(dir)
+        .map_err(io::Error::other)?;
+
+    let (filter, handle) = reload::Layer::new(level.filter());
+    let file = fmt::layer().with_writer(appender).with_ansi(false);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file)
+        .try_init()
+        .map_err(io::Error::other)?;
+
+    let _ = LEVEL_HANDLE.set(handle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_include_all_less_detailed_levels() {
+        let filters = LogLevel::all_levels().map(|level| level.filter());
+        assert_eq!(filters[0], LevelFilter::OFF);
+        assert!(filters.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(LogLevel::default().filter(), LevelFilter::INFO);
+    }
+}
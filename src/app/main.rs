@@ -1,80 +1,334 @@
-use super::overlay::draw_overlay;
-use super::{colors::ColorMap, fonts, system_access::get_game};
-use crate::{Backend, GameData, MissionStats, Window};
+use super::backends::missions::catalogs;
+use super::colors::{ColorMap, ColorMapEditor};
+use super::history::{format_date, MissionHistory};
+use super::hotkeys::{write_marker, Hotkey, HotkeyAction, HotkeyListener, HotkeySettings};
+use super::i18n::{self, mission_name, tr, tr_args, Language};
+use super::launch::{LaunchOptions, APP_NAME};
+use super::layout::{LayoutPreset, LayoutSettings};
+use super::logging::{self, LogLevel};
+use super::overlay::{
+    draw_overlay, OverlayAnchor, OverlayContent, OverlayLayout, OverlayPosition, OverlaySettings,
+    OverlayStyle,
+};
+use super::profiles::{Profile, ProfileSettings};
+use super::recording::GameRecorder;
+use super::settings::{Records, Settings, Theme};
+use super::statistics::{played_missions, MissionSummary};
+use super::system_access::{find_games, process_running, GameProcess, ProcessPin};
+use super::time_format::{TimeFormat, TICKS_PER_SECOND};
+use super::timeline::MissionTimeline;
+use super::{campaign::CampaignRun, fonts, fonts::OverlayFont, livesplit, rta::RtaTimer};
+use crate::{Backend, Difficulty, GameData, MissionStats, Rating, SaRisk, Window, STAT_NAMES};
 
 use egui::*;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
 use sysinfo::System;
+use tracing::{debug, info, warn};
 
-// Set the minimum refresh rate of the app in Hz
-// Note: Refresh rate will increase if the cursor is moved around while the window is in focus
-const RUNNING_REFRESH_RATE: usize = 30;
-const WAITING_REFRESH_RATE: usize = 1;
+// Set the rate in Hz at which the position and state of the game window is updated
+const WINDOW_REFRESH_RATE: usize = 4;
 
-#[derive(serde::Deserialize, serde::Serialize)]
-#[serde(default)]
 /// Application struct that holds the GUI state and required data
-/// The user settings are made persistent using Serde to keep the settings
-/// consistent between application sessions.
+/// The user settings are kept in a separate versioned struct that is made persistent
+/// to keep the settings consistent between application sessions.
 pub struct App {
     // Application data (Not persistent)
-    #[serde(skip)]
     state: State,
-
-    #[serde(skip)]
     game: Option<Box<dyn Backend>>,
-
-    #[serde(skip)]
     game_window: Option<Window>,
-
-    #[serde(skip)]
+    window_updated: Instant,
     sys: System,
+    cmap_editor: ColorMapEditor,
+    rta: RtaTimer,
+    campaign: CampaignRun,
+    timeline: MissionTimeline,
+    hotkey_listener: HotkeyListener,
+    hotkey_recording: Option<HotkeyAction>,
+    hotkey_status: String,
+    lss_status: String,
+    history_status: String,
+    statistics_mission: String,
+    statistics_difficulty: Difficulty,
+    profile_name: String,
+    profile_status: String,
+    launch: LaunchOptions,
+    overrides: SessionOverrides,
+    recorder: Option<GameRecorder>,
+    processes: Vec<GameProcess>,
+    attached: Option<GameProcess>,
+    pin: ProcessPin,
 
     // User settings (Persistent)
-    cmap: ColorMap,
-    show_overlay: bool,
-    overlay_size: u8,
-    theme: Visuals,
+    settings: Settings,
 }
 
 /// Enum to track the different states of the application
 /// Each state has a corresponding GUI layout that is displayed
+#[derive(Copy, Clone, PartialEq, Debug)]
 enum State {
     Running,
     Settings,
+    OverlaySettings,
+    CustomColors,
+    SplitFiles,
+    History,
+    Statistics,
+    Hotkeys,
+    Layout,
+    Profiles,
+    Processes,
     Waiting,
 }
 
+/// Settings replaced by launch options for the current session only. The fields hold the
+/// launch values until they are swapped into the settings, and the stored values afterwards.
+#[derive(Default)]
+struct SessionOverrides {
+    show_overlay: Option<bool>,
+    preset: Option<LayoutPreset>,
+    log_level: Option<LogLevel>,
+}
+
+impl SessionOverrides {
+    fn from_launch(launch: &LaunchOptions) -> Self {
+        SessionOverrides {
+            show_overlay: launch.overlay.then_some(true),
+            preset: launch.compact.then_some(LayoutPreset::Compact),
+            log_level: launch.log_level,
+        }
+    }
+
+    /// Exchange the overridden values with those of the settings
+    fn swap(&mut self, settings: &mut Settings) {
+        if let Some(show_overlay) = self.show_overlay.as_mut() {
+            std::mem::swap(show_overlay, &mut settings.show_overlay);
+        }
+        if let Some(preset) = self.preset.as_mut() {
+            std::mem::swap(preset, &mut settings.layout.preset);
+        }
+        if let Some(level) = self.log_level.as_mut() {
+            std::mem::swap(level, &mut settings.log_level);
+        }
+    }
+}
+
 impl Default for App {
     fn default() -> Self {
         Self {
             state: State::Waiting,
             game: None,
             game_window: None,
+            window_updated: Instant::now(),
             sys: System::new(),
-            cmap: ColorMap::default(),
-            show_overlay: false,
-            overlay_size: 5,
-            theme: Visuals::dark(),
+            cmap_editor: ColorMapEditor::from_cmap(&ColorMap::default()),
+            rta: RtaTimer::default(),
+            campaign: CampaignRun::default(),
+            timeline: MissionTimeline::default(),
+            hotkey_listener: HotkeyListener::default(),
+            hotkey_recording: None,
+            hotkey_status: String::new(),
+            lss_status: String::new(),
+            history_status: String::new(),
+            statistics_mission: String::new(),
+            statistics_difficulty: Difficulty::default(),
+            profile_name: String::from("Default"),
+            profile_status: String::new(),
+            launch: LaunchOptions::default(),
+            overrides: SessionOverrides::default(),
+            recorder: None,
+            processes: Vec::new(),
+            attached: None,
+            pin: ProcessPin::default(),
+            settings: Settings::default(),
         }
     }
 }
 
 impl App {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, launch: LaunchOptions) -> Self {
         fonts::load_monospace_font(&cc.egui_ctx);
 
-        // Load the user settings from the previous session if they exist
-        if let Some(storage) = cc.storage {
-            let app_data: App = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-            // Theme is not saved by egui itself so we set it manually at launch
-            cc.egui_ctx.set_visuals(app_data.theme.clone());
+        // Write the log next to the settings file, or in the default storage directory
+        let log_dir = match launch.config.as_deref() {
+            Some(config) => config.parent().map(Path::to_path_buf),
+            None => eframe::storage_dir(APP_NAME),
+        };
+        // The tracker runs without a log file if it cannot be created
+        let log_level = launch.log_level.unwrap_or_default();
+        let _ = logging::init(&log_dir.unwrap_or_default(), log_level);
+        info!(version = env!("CARGO_PKG_VERSION"), "StatTracker started");
+
+        // Load the user settings from the previous session, migrating older versions
+        let mut settings = Settings::load(cc.storage);
+
+        // Theme and language are not saved by egui itself so we set them manually at launch
+        cc.egui_ctx.set_visuals(settings.theme.visuals());
+        i18n::set_language(settings.language);
+
+        // Settings selected on the command line replace the stored ones for this session
+        let mut overrides = SessionOverrides::from_launch(&launch);
+        overrides.swap(&mut settings);
+        logging::set_level(settings.log_level);
+        if launch.compact {
+            cc.egui_ctx.send_viewport_cmd(ViewportCommand::InnerSize(
+                settings.layout.preset.base_size(),
+            ));
+        }
+
+        // The tracker runs without recording if the file cannot be opened
+        let recorder = launch.record.as_deref().and_then(|path| {
+            GameRecorder::create(path)
+                .inspect_err(|err| warn!(%err, path = %path.display(), "Failed to start recording"))
+                .ok()
+        });
+
+        // The game and process selected on the command line are pinned
+        let pin = ProcessPin {
+            game: launch.game,
+            pid: launch.pid,
+        };
+
+        Self {
+            settings,
+            launch,
+            overrides,
+            recorder,
+            pin,
+            ..Default::default()
+        }
+    }
+
+    /// Replace the current user settings with those of a profile
+    fn apply_profile_settings(&mut self, ctx: &egui::Context, profile: ProfileSettings) {
+        self.settings.apply_profile(profile);
+
+        // Theme and language are global state that has to be updated as well
+        ctx.set_visuals(self.settings.theme.visuals());
+        i18n::set_language(self.settings.language);
+    }
+
+    /// Start reading the data of a game process, or resume reading it after the settings
+    fn attach(&mut self, process: GameProcess) {
+        self.state = State::Running;
+
+        // The backend of the process that is already read is kept
+        if self
+            .game
+            .as_ref()
+            .is_some_and(|game| game.pid() == process.pid)
+        {
+            self.attached = Some(process);
+            return;
+        }
+
+        let note = format!(
+            "Attached to {} (pid {})",
+            process.game.game_name(),
+            process.pid
+        );
+        self.record(|recorder| recorder.note(&note));
+        info!(game = ?process.game, pid = process.pid, "Attached to game process");
+
+        // The running attempt ends when switching to another process
+        if self.game.is_some() {
+            self.settings.history.finish();
+        }
+
+        self.game = Some(process.attach());
+
+        // Games offer different difficulties, e.g. Rookie only exists in Contracts
+        let difficulties = self.game.as_ref().unwrap().difficulties();
+        if !difficulties.contains(&self.settings.difficulty) {
+            self.settings.difficulty = Difficulty::default();
+        }
+        self.attached = Some(process);
+        self.game_window = None;
+    }
+
+    /// Stop reading the game once it is closed. A process pin is moved to the game,
+    /// so that the next instance of the same game is attached to automatically.
+    fn detach(&mut self) {
+        self.record(|recorder| recorder.note("Game closed"));
+        info!("Detached from game process");
+        self.settings.history.finish();
+
+        if let Some(process) = self.attached.take() {
+            self.pin.release(&process);
+        }
+
+        self.game = None;
+        self.game_window = None;
+        self.state = State::Waiting;
+    }
+
+    /// Write to the recording, stopping it if the file can no longer be written
+    fn record(&mut self, write: impl FnOnce(&mut GameRecorder) -> io::Result<()>) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if write(recorder).is_err() {
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Poll the global hotkeys and perform the triggered actions.
+    /// Hotkeys only act while the game or the tracker itself has focus, and the actions
+    /// that need the game data are ignored while no game is attached.
+    fn handle_hotkeys(&mut self, ctx: &egui::Context, data: Option<&GameData>) {
+        if !self.settings.hotkeys.enabled || self.hotkey_recording.is_some() {
+            return;
+        }
 
-            return app_data;
+        // Always poll to keep track of released keys, even when the actions are ignored
+        let actions = self.hotkey_listener.poll(&self.settings.hotkeys.bindings);
+        let game_focused = self
+            .game_window
+            .as_ref()
+            .is_some_and(|window| window.focused);
+        if !game_focused && !ctx.input(|i| i.focused) {
+            return;
         }
 
-        // If no settings are found, use the default settings
-        Default::default()
+        for action in actions {
+            match action {
+                HotkeyAction::ToggleOverlay => {
+                    self.settings.show_overlay = !self.settings.show_overlay
+                }
+                HotkeyAction::ToggleCompact => {
+                    self.settings.layout.toggle_compact();
+                    ctx.send_viewport_cmd(ViewportCommand::InnerSize(
+                        self.settings.layout.preset.base_size(),
+                    ));
+                }
+                HotkeyAction::Split => {
+                    if self.settings.campaign_mode && data.is_some() {
+                        self.campaign
+                            .finish(&mut self.settings.records().best_times);
+                    }
+                }
+                HotkeyAction::ResetTimer => self.rta.reset(),
+                HotkeyAction::Marker => {
+                    let Some(data) = data else { continue };
+                    let text = format!(
+                        "{}  IGT {}  RTA {}",
+                        data.mission_name,
+                        self.settings.time_format.format_ticks(data.mission_time),
+                        self.settings.time_format.format_seconds(self.rta.seconds())
+                    );
+
+                    let path = Path::new(&self.settings.hotkeys.marker_log);
+                    self.hotkey_status = match write_marker(path, &text) {
+                        Ok(()) => tr_args("Marker added: {}", &[&text]),
+                        Err(err) => {
+                            warn!(%err, path = %path.display(), "Failed to write marker");
+                            tr_args("Marker failed: {}", &[&err])
+                        }
+                    };
+                }
+            }
+        }
     }
 }
 
@@ -83,106 +337,550 @@ impl App {
 impl eframe::App for App {
     /// Save the user settings to the storage on application close
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+        // The stored values of settings overridden at launch are saved instead
+        self.overrides.swap(&mut self.settings);
+        self.settings.save(storage);
+        self.overrides.swap(&mut self.settings);
+    }
+
+    /// Clear to a transparent color so that the background of the overlay can be transparent
+    fn clear_color(&self, _visuals: &Visuals) -> [f32; 4] {
+        [0.0; 4]
     }
 
     /// Draw the GUI for the application
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let previous_state = self.state;
+
+        // The menus need the full window size, even if a smaller layout is selected
+        if !matches!(self.state, State::Running | State::Waiting) {
+            let min_size = LayoutPreset::Full.base_size();
+            let size = ctx.screen_rect().size();
+            if size.x < min_size.x || size.y < min_size.y {
+                ctx.send_viewport_cmd(ViewportCommand::InnerSize(size.max(min_size)));
+            }
+        }
+
+        // Keep polling the hotkeys in the menus, so that presses are not reported late
+        if !matches!(self.state, State::Running) {
+            self.handle_hotkeys(ctx, None);
+        }
+
         // If game is running, update game stored game data, otherwise check if game
         // is running and update state accordingly
         match self.state {
             State::Waiting => {
-                display_no_game(ctx, &mut self.state);
-                if let Some(game) = get_game(&mut self.sys) {
-                    self.game = Some(game);
-                    self.state = State::Running;
+                self.processes = find_games(&mut self.sys);
+
+                // A process that exited while the settings were open is detached as well
+                let exited = self
+                    .attached
+                    .as_ref()
+                    .is_some_and(|process| !self.processes.contains(process));
+                if exited {
+                    self.detach();
+                }
+
+                // Resume the previous process, e.g. after leaving the settings, or attach
+                // automatically if only a single process matches the pin
+                let candidates: Vec<GameProcess> = self
+                    .processes
+                    .iter()
+                    .filter(|process| self.pin.matches(process))
+                    .cloned()
+                    .collect();
+                let previous = self.attached.take();
+                let selected = match candidates.as_slice() {
+                    [process] => previous.or_else(|| Some(process.clone())),
+                    _ => previous,
+                };
+
+                // Otherwise the user picks one of the matching processes
+                let choices = if selected.is_none() {
+                    &candidates[..]
+                } else {
+                    &[]
+                };
+                let picked = display_no_game(ctx, choices, &mut self.state);
+
+                if let Some(process) = selected.or(picked) {
+                    self.attach(process);
                 }
 
                 // Force redraw of the GUI
                 ctx.request_repaint_after(Duration::from_millis(
-                    (1000 / WAITING_REFRESH_RATE) as u64,
+                    (1000 / self.launch.waiting_refresh_rate) as u64,
                 ))
             }
             State::Running => {
                 match self.game.as_mut().unwrap().update() {
                     Some(game_data) => {
-                        display_game_data(ctx, &game_data, &mut self.state, &self.cmap);
+                        self.record(|recorder| recorder.record(&game_data));
+                        self.rta.update(&game_data);
+                        self.timeline.update(&game_data);
+                        self.handle_hotkeys(ctx, Some(&game_data));
+                        self.settings.history.update(
+                            &game_data,
+                            self.game.as_deref().unwrap(),
+                            self.settings.difficulty,
+                        );
+
+                        // Show the splits of the run instead of the mission stats in campaign mode
+                        if self.settings.campaign_mode {
+                            let records = self
+                                .settings
+                                .records
+                                .entry(self.settings.difficulty)
+                                .or_default();
+                            self.campaign.update(
+                                &game_data,
+                                self.game.as_ref().unwrap().missions(),
+                                &mut records.best_times,
+                            );
+
+                            display_campaign(
+                                ctx,
+                                &game_data,
+                                &self.game.as_ref().unwrap().missions().mission_list(),
+                                &mut self.campaign,
+                                &mut records.best_times,
+                                &mut self.state,
+                                &self.settings.cmap,
+                                &self.settings.time_format,
+                                self.settings.difficulty,
+                            );
+                        } else {
+                            display_game_data(
+                                ctx,
+                                &game_data,
+                                &mut self.state,
+                                &self.settings.cmap,
+                                &self.settings.time_format,
+                                self.settings.show_rta.then(|| self.rta.seconds()),
+                                self.settings
+                                    .records
+                                    .get(&self.settings.difficulty)
+                                    .zip(game_data.mission_code.as_ref())
+                                    .and_then(|(records, code)| records.split_targets.get(code))
+                                    .copied(),
+                                &self.timeline,
+                                &self.settings.layout,
+                                self.settings.difficulty,
+                            );
+                        }
+
+                        // Hide the overlay while the game is minimized or, if selected, unfocused
+                        let game_visible = self.game_window.as_ref().map_or(true, |window| {
+                            !window.minimized
+                                && (window.focused || !self.settings.overlay.hide_unfocused)
+                        });
 
                         // Draw the overlay if enabled
-                        if self.show_overlay {
+                        if self.settings.show_overlay && game_visible {
                             draw_overlay(
                                 ctx,
-                                &self.cmap,
+                                &self.settings.cmap,
                                 &self.game_window,
-                                &self.overlay_size,
-                                &game_data.mission_time,
-                                &game_data.rating.map_or(true, |r| r.sa_rating),
+                                &self.settings.overlay_size,
+                                &mut self.settings.overlay,
+                                &self.settings.time_format,
+                                self.rta.seconds(),
+                                &game_data,
                             );
                         }
                     }
                     None => {
-                        self.state = State::Waiting;
-                        self.game_window = None;
-                        display_no_game(ctx, &mut self.state);
+                        self.detach();
+                        display_no_game(ctx, &[], &mut self.state);
                     }
                 }
 
-                // If game is running but window is not set, set the window
-                if self.game_window.is_none() {
-                    self.game_window = self.game.as_ref().unwrap().game_window();
+                // Periodically update the game window to follow it when moved, resized
+                // or minimized. Retry every frame until the window is found.
+                let window_refresh = Duration::from_millis((1000 / WINDOW_REFRESH_RATE) as u64);
+                if let Some(game) = self.game.as_ref() {
+                    if self.game_window.is_none() || self.window_updated.elapsed() >= window_refresh
+                    {
+                        self.game_window = game.game_window();
+                        self.window_updated = Instant::now();
+
+                        // The memory of an exited process may still be readable for a moment
+                        let exited = self
+                            .attached
+                            .as_ref()
+                            .is_some_and(|process| !process_running(&mut self.sys, process));
+                        if exited {
+                            self.detach();
+                        }
+                    }
                 }
 
                 // Force redraw of the GUI
                 ctx.request_repaint_after(Duration::from_millis(
-                    (1000 / RUNNING_REFRESH_RATE) as u64,
+                    (1000 / self.launch.running_refresh_rate) as u64,
                 ))
             }
 
             State::Settings => {
-                display_settings(
-                    ctx,
-                    &mut self.cmap,
-                    &mut self.show_overlay,
-                    &mut self.overlay_size,
-                    &mut self.state,
-                    &mut self.theme,
-                );
+                let all_difficulties = Difficulty::all_difficulties();
+                let difficulties = self
+                    .game
+                    .as_ref()
+                    .map_or(&all_difficulties[..], |game| game.difficulties());
+                display_settings(ctx, &mut self.settings, difficulties, &mut self.state);
+
+                // List the running game processes when opening the process picker
+                if matches!(self.state, State::Processes) {
+                    self.processes = find_games(&mut self.sys);
+                }
+
+                // Start editing from the currently selected color map
+                if matches!(self.state, State::CustomColors) {
+                    self.cmap_editor = ColorMapEditor::from_cmap(&self.settings.cmap);
+                }
+
+                // Return to the window size of the selected layout when leaving the settings
+                if matches!(self.state, State::Waiting) {
+                    ctx.send_viewport_cmd(ViewportCommand::InnerSize(
+                        self.settings.layout.preset.base_size(),
+                    ));
+                }
 
                 // Draw the overlay if enabled
-                if self.show_overlay {
+                if self.settings.show_overlay {
                     draw_overlay(
                         ctx,
-                        &self.cmap,
+                        &self.settings.cmap,
                         &self.game_window,
-                        &self.overlay_size,
-                        &0,
-                        &true,
+                        &self.settings.overlay_size,
+                        &mut self.settings.overlay,
+                        &self.settings.time_format,
+                        0.0,
+                        &overlay_preview(),
                     );
                 }
             }
+
+            State::CustomColors => {
+                display_custom_colors(
+                    ctx,
+                    &mut self.cmap_editor,
+                    &mut self.settings.cmap,
+                    &mut self.settings.custom_cmaps,
+                    &mut self.state,
+                );
+            }
+
+            State::SplitFiles => {
+                let records = self
+                    .settings
+                    .records
+                    .entry(self.settings.difficulty)
+                    .or_default();
+                display_split_files(
+                    ctx,
+                    &mut self.settings.lss_path,
+                    &mut self.lss_status,
+                    &self.campaign,
+                    self.settings.difficulty,
+                    records,
+                    &mut self.state,
+                );
+            }
+
+            State::History => {
+                display_history(
+                    ctx,
+                    &mut self.settings.history,
+                    &mut self.settings.history_path,
+                    &mut self.history_status,
+                    &mut self.state,
+                );
+            }
+
+            State::Statistics => {
+                display_statistics(
+                    ctx,
+                    &self.settings.history,
+                    &mut self.statistics_mission,
+                    &mut self.statistics_difficulty,
+                    &self.settings.time_format,
+                    &mut self.state,
+                );
+            }
+
+            State::Hotkeys => {
+                display_hotkeys(
+                    ctx,
+                    &mut self.settings.hotkeys,
+                    &mut self.hotkey_recording,
+                    &self.hotkey_status,
+                    &mut self.state,
+                );
+            }
+
+            State::Layout => {
+                display_layout_settings(ctx, &mut self.settings.layout, &mut self.state);
+            }
+
+            State::Profiles => {
+                if let Some(profile) = display_profiles(
+                    ctx,
+                    &mut self.settings,
+                    &mut self.profile_name,
+                    &mut self.profile_status,
+                    &mut self.state,
+                ) {
+                    self.apply_profile_settings(ctx, profile);
+                }
+            }
+
+            State::Processes => {
+                display_processes(
+                    ctx,
+                    &mut self.sys,
+                    &mut self.processes,
+                    &mut self.attached,
+                    &mut self.pin,
+                    &mut self.state,
+                );
+            }
+
+            State::OverlaySettings => {
+                display_overlay_settings(ctx, &mut self.settings.overlay, &mut self.state);
+
+                // Always show the overlay while configuring it
+                draw_overlay(
+                    ctx,
+                    &self.settings.cmap,
+                    &self.game_window,
+                    &self.settings.overlay_size,
+                    &mut self.settings.overlay,
+                    &self.settings.time_format,
+                    0.0,
+                    &overlay_preview(),
+                );
+            }
+        }
+
+        if self.state != previous_state {
+            debug!(from = ?previous_state, to = ?self.state, "State changed");
         }
     }
 }
 
 /// Draw GUI for the application when a game is running
-fn display_game_data(ctx: &egui::Context, data: &GameData, app_state: &mut State, cmap: &ColorMap) {
+#[allow(clippy::too_many_arguments)]
+fn display_game_data(
+    ctx: &egui::Context,
+    data: &GameData,
+    app_state: &mut State,
+    cmap: &ColorMap,
+    time_format: &TimeFormat,
+    rta: Option<f32>,
+    target: Option<f32>,
+    timeline: &MissionTimeline,
+    layout: &LayoutSettings,
+    difficulty: Difficulty,
+) {
+    let preset = layout.preset;
+    let scale = layout.text_scale(ctx.screen_rect().size());
+
     egui::CentralPanel::default().show(ctx, |ui| {
+        // Open the settings by clicking anywhere in the window, as some layouts have no timer
+        let background = ui.interact(ui.max_rect(), Id::new("GameDataBackground"), Sense::click());
+        if background.clicked() {
+            *app_state = State::Settings;
+        }
+
+        // Timer and rating on a single row
+        if preset == LayoutPreset::Compact {
+            ui.horizontal_centered(|ui| {
+                let timer_text = timer_text(ui, data.mission_time, time_format, rta, 24.0 * scale);
+                if ui.add(Button::new(timer_text).frame(false)).clicked() {
+                    *app_state = State::Settings;
+                }
+
+                if let Some(stats) = data.rating {
+                    ui.label(
+                        egui::RichText::new(tr("SA"))
+                            .size(20.0 * scale)
+                            .monospace()
+                            .color(cmap.get_rating_color(stats.sa_risk)),
+                    );
+                }
+            });
+            return;
+        }
+
         // Mission Title / Game Status
         ui.vertical_centered(|ui| {
-            ui.heading(egui::RichText::new(&data.mission_name).size(20.0));
+            if preset.show_heading() {
+                let name = mission_name(data.mission_code.as_deref(), &data.mission_name);
+                let heading = ui.heading(egui::RichText::new(name).size(20.0 * scale));
+                if let Some(details) = mission_details(data.mission_code.as_deref()) {
+                    heading.on_hover_text(details);
+                }
+                display_difficulty(ui, difficulty, 14.0 * scale);
+                ui.separator();
+            }
+
+            // Mission Time, followed by the real time if enabled
+            if preset != LayoutPreset::StatsOnly {
+                let timer_text = timer_text(ui, data.mission_time, time_format, rta, 40.0 * scale);
+                if ui.add(Button::new(timer_text).frame(false)).clicked() {
+                    *app_state = State::Settings;
+                }
+
+                // Imported split target of the mission
+                if let Some(target) = target {
+                    ui.label(
+                        egui::RichText::new(format!("PB {}", time_format.format_ticks(target)))
+                            .size(14.0 * scale)
+                            .monospace()
+                            .color(ui.visuals().weak_text_color()),
+                    );
+                }
+            }
+
+            // Mission Stats
+            if preset.show_stats() {
+                ui.horizontal(|ui| {
+                    ui.add_space(20.0 * scale);
+                    egui::Grid::new("Stats")
+                        .num_columns(2)
+                        .spacing([20.0 * scale, 2.0])
+                        .show(ui, |ui| {
+                            let stats = data.rating.map(|r| r.stats).unwrap_or_default();
+                            format_stats(ui, stats, layout, scale);
+                        });
+                });
+            }
+
+            // Mission Rating
+            if preset.show_rating() {
+                ui.add_space(4.0);
+                if let Some(stats) = data.rating {
+                    ui.label(
+                        egui::RichText::new(tr("SILENT ASSASSIN"))
+                            .size(25.0 * scale)
+                            .monospace()
+                            .color(cmap.get_rating_color(stats.sa_risk)),
+                    );
+                }
+            }
+        });
+
+        // Stat changes over the mission, kept after the mission for review
+        if preset == LayoutPreset::Full {
+            egui::CollapsingHeader::new(tr("Timeline"))
+                .id_source("MissionTimeline")
+                .show(ui, |ui| timeline_plot(ui, timeline, cmap, time_format));
+        }
+    });
+}
+
+/// Mission time with the real time in a smaller font after it, if enabled
+fn timer_text(
+    ui: &Ui,
+    mission_time: f32,
+    time_format: &TimeFormat,
+    rta: Option<f32>,
+    size: f32,
+) -> egui::text::LayoutJob {
+    let mut timer_text = egui::text::LayoutJob::default();
+
+    timer_text.append(
+        &time_format.format_ticks(mission_time),
+        0.0,
+        TextFormat {
+            font_id: FontId::monospace(size),
+            color: ui.visuals().text_color(),
+            ..Default::default()
+        },
+    );
+
+    if let Some(rta) = rta {
+        timer_text.append(
+            &format!("RTA {}", time_format.format_seconds(rta)),
+            size / 4.0,
+            TextFormat {
+                font_id: FontId::monospace(size * 0.4),
+                color: ui.visuals().weak_text_color(),
+                ..Default::default()
+            },
+        );
+    }
+
+    timer_text
+}
+
+/// Location, story position and silent assassin notes of a mission from the mission catalog
+fn mission_details(code: Option<&str>) -> Option<String> {
+    let code = code?;
+    let (catalog, mission) = catalogs()
+        .into_iter()
+        .find_map(|catalog| Some((catalog, catalog.get(code)?)))?;
+
+    let mut details = Vec::new();
+    if !mission.location.is_empty() {
+        details.push(tr(mission.location).to_string());
+    }
+    if let Some(order) = catalog.story_order(code) {
+        details.push(tr_args(
+            "Mission {} of {}",
+            &[&(order + 1), &catalog.missions().len()],
+        ));
+    }
+    if !mission.ratings {
+        details.push(tr("No rating").to_string());
+    }
+    if !mission.sa_notes.is_empty() {
+        details.push(tr(mission.sa_notes).to_string());
+    }
+
+    Some(details.join("\n"))
+}
+
+/// Show the difficulty that the records are kept for below the mission name
+fn display_difficulty(ui: &mut Ui, difficulty: Difficulty, size: f32) {
+    ui.label(
+        egui::RichText::new(tr(difficulty.get_label()))
+            .size(size)
+            .color(ui.visuals().weak_text_color()),
+    );
+}
+
+/// Draw GUI for the application in campaign mode, showing the splits of all missions
+#[allow(clippy::too_many_arguments)]
+fn display_campaign(
+    ctx: &egui::Context,
+    data: &GameData,
+    missions: &[(String, String)],
+    campaign: &mut CampaignRun,
+    best_times: &mut HashMap<String, f32>,
+    app_state: &mut State,
+    cmap: &ColorMap,
+    time_format: &TimeFormat,
+    difficulty: Difficulty,
+) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            let name = mission_name(data.mission_code.as_deref(), &data.mission_name);
+            let heading = ui.heading(egui::RichText::new(name).size(20.0));
+            if let Some(details) = mission_details(data.mission_code.as_deref()) {
+                heading.on_hover_text(details);
+            }
+            display_difficulty(ui, difficulty, 14.0);
             ui.separator();
 
-            // Mission Time
+            // Total run time
             if ui
                 .add(
                     Button::new(
-                        egui::RichText::new(format!(
-                            "{:0>2}:{:0>2}",
-                            data.mission_time / 3600,
-                            (data.mission_time / 60) % 60,
-                        ))
-                        .size(40.0)
-                        .monospace(),
+                        egui::RichText::new(time_format.format_ticks(campaign.total_time()))
+                            .size(30.0)
+                            .monospace(),
                     )
                     .frame(false),
                 )
@@ -190,142 +888,310 @@ fn display_game_data(ctx: &egui::Context, data: &GameData, app_state: &mut State
             {
                 *app_state = State::Settings;
             }
+        });
 
-            // Mission Stats
-            ui.horizontal(|ui| {
-                ui.add_space(20.0);
-                egui::Grid::new("Stats")
-                    .num_columns(2)
-                    .spacing([20.0, 2.0])
+        // Split table with a row for each mission in story order
+        egui::ScrollArea::vertical()
+            .max_height(170.0)
+            .show(ui, |ui| {
+                egui::Grid::new("Splits")
+                    .num_columns(4)
+                    .spacing([8.0, 2.0])
+                    .striped(true)
                     .show(ui, |ui| {
-                        if let Some(rating) = data.rating {
-                            format_stats(ui, rating.stats);
-                        } else {
-                            format_stats(ui, MissionStats::default());
+                        for (code, mission) in missions {
+                            let split = campaign.get_split(code);
+
+                            // Highlight the mission currently being played
+                            let mut name = egui::RichText::new(mission_name(Some(code), mission));
+                            if split.is_some_and(|(_, current)| current) {
+                                name = name.strong();
+                            }
+                            ui.add_sized([110.0, 16.0], egui::Label::new(name).truncate());
+
+                            match split {
+                                Some((split, current)) => {
+                                    ui.monospace(time_format.format_ticks(split.time));
+
+                                    match split.sa_risk {
+                                        Some(risk) => {
+                                            ui.colored_label(cmap.get_rating_color(risk), tr("SA"))
+                                        }
+                                        None => ui.label("-"),
+                                    };
+
+                                    // The current attempt is compared live to the best time
+                                    let delta = if current {
+                                        best_times.get(code).map(|best| split.time - best)
+                                    } else {
+                                        split.delta
+                                    };
+                                    ui.monospace(delta.map_or(String::new(), format_delta));
+                                }
+                                None => {
+                                    ui.monospace("-");
+                                    ui.label("");
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
                         }
                     });
             });
 
-            // Mission Rating
-            ui.add_space(4.0);
-            if let Some(stats) = data.rating {
-                ui.label(
-                    egui::RichText::new("SILENT ASSASSIN")
-                        .size(25.0)
-                        .monospace()
-                        .color(cmap.get_rating_color(stats.sa_rating)),
-                );
+        // Totals of the run, with the aggregated stats shown on hover
+        ui.separator();
+        let (sa, rated) = campaign.sa_count();
+        let played = missions
+            .iter()
+            .filter(|(code, _)| campaign.get_split(code).is_some())
+            .count();
+
+        let stats = campaign.total_stats();
+        ui.label(tr_args(
+            "SA {}/{}  -  {} missions played",
+            &[&sa, &rated, &played],
+        ))
+        .on_hover_ui(|ui| {
+            egui::Grid::new("TotalStats").num_columns(2).show(ui, |ui| {
+                format_stats(ui, stats, &LayoutSettings::default(), 1.0);
+            });
+        });
+
+        // Missions are completed from the game, finishing by hand keeps the current attempt
+        ui.horizontal(|ui| {
+            if ui.button(tr("Finish Run")).clicked() {
+                campaign.finish(best_times);
+            }
+            if ui.button(tr("Reset Run")).clicked() {
+                campaign.reset();
             }
         });
     });
 }
 
-/// Draw GUI for the application while waiting for a compatible game to launch
-fn display_no_game(ctx: &egui::Context, app_state: &mut State) {
+/// Format the difference to a best time given in ticks as signed seconds
+fn format_delta(delta: f32) -> String {
+    format!("{:+.2}", delta / TICKS_PER_SECOND)
+}
+
+/// Draw GUI for the application when no game is running. If several game processes are
+/// running, they are listed to pick the one to attach to. Returns the picked process.
+fn display_no_game(
+    ctx: &egui::Context,
+    processes: &[GameProcess],
+    app_state: &mut State,
+) -> Option<GameProcess> {
+    let mut picked = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.vertical_centered(|ui| {
             // Heading
             ui.add_space(12.0);
             ui.label(egui::RichText::new("Hitman StatTracker").size(25.0));
 
-            // Subheading
-            ui.add_space(30.0);
-            ui.label(
-                egui::RichText::new("Launch Hitman 2 SA or \nHitman Contracts to show stats")
+            if processes.len() > 1 {
+                // Process picker
+                ui.add_space(15.0);
+                ui.label(
+                    egui::RichText::new(tr("Several games are running.\nSelect one to show stats"))
+                        .size(14.0),
+                );
+                ui.add_space(5.0);
+                egui::ScrollArea::vertical()
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for process in processes {
+                            if ui.button(process_label(process)).clicked() {
+                                picked = Some(process.clone());
+                            }
+                        }
+                    });
+                ui.add_space(10.0);
+            } else {
+                // Subheading
+                ui.add_space(30.0);
+                ui.label(
+                    egui::RichText::new(tr(
+                        "Launch Hitman 2 SA or \nHitman Contracts to show stats",
+                    ))
                     .size(16.0),
-            );
+                );
+                ui.add_space(167.0);
+            }
 
             // Open Settings Button
-            ui.add_space(167.0);
             if ui
-                .button(egui::RichText::new("Settings").size(15.0))
+                .button(egui::RichText::new(tr("Settings")).size(15.0))
                 .clicked()
             {
                 *app_state = State::Settings;
             }
         });
     });
+
+    picked
+}
+
+/// Name, process ID and start time of a game process
+fn process_label(process: &GameProcess) -> String {
+    format!(
+        "{}\n{}",
+        process.game.game_name(),
+        tr_args(
+            "PID {}, started {}",
+            &[&process.pid, &format_date(process.start_time)]
+        )
+    )
 }
 
 /// Display the settings menu
 fn display_settings(
     ctx: &egui::Context,
-    cmap: &mut ColorMap,
-    show_overlay: &mut bool,
-    overlay_size: &mut u8,
+    settings: &mut Settings,
+    difficulties: &[Difficulty],
     app_state: &mut State,
-    theme: &mut Visuals,
 ) {
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.vertical_centered(|ui| {
             // Heading
-            ui.heading(egui::RichText::new("Settings").size(20.0));
+            ui.heading(egui::RichText::new(tr("Settings")).size(20.0));
             ui.separator();
         });
 
-        // Create grid for all the settings
-        egui::Grid::new("Settings")
-            .num_columns(2)
-            .spacing([25.0, 5.0])
+        // Create grid for all the settings, scrollable to keep the menu within the window
+        egui::ScrollArea::vertical()
+            .max_height(140.0)
             .show(ui, |ui| {
-                // Theme Toggle
-                ui.add(egui::Label::new("Theme"));
-                theme_toggle(ui, ctx, theme);
-                ui.end_row();
+                egui::Grid::new("Settings")
+                    .num_columns(2)
+                    .spacing([25.0, 5.0])
+                    .show(ui, |ui| {
+                        // Named sets of settings
+                        ui.add(egui::Label::new(tr("Profile")));
+                        if ui.button(&settings.active_profile).clicked() {
+                            *app_state = State::Profiles;
+                        }
+                        ui.end_row();
 
-                // Color map selector
-                ui.add(egui::Label::new("Rating Colors"));
-                cmap_selector(ui, cmap);
-                ui.end_row();
+                        // Game process to read the data from
+                        ui.add(egui::Label::new(tr("Game Process")));
+                        if ui.button(tr("Select")).clicked() {
+                            *app_state = State::Processes;
+                        }
+                        ui.end_row();
 
-                // Use game overlay
-                ui.add(egui::Label::new("Game Overlay"));
-                ui.checkbox(show_overlay, "Enable");
-                ui.end_row();
+                        // Theme Toggle
+                        ui.add(egui::Label::new(tr("Theme")));
+                        theme_toggle(ui, ctx, &mut settings.theme);
+                        ui.end_row();
 
-                // Text size of the overlay
-                ui.add(egui::Label::new("Overlay Size"));
+                        // Language of the interface and the mission names
+                        ui.add(egui::Label::new(tr("Language")));
+                        language_selector(ui, &mut settings.language);
+                        ui.end_row();
 
-                ui.add(egui::Slider::new(overlay_size, 1..=10));
-            });
+                        // Color map selector
+                        ui.add(egui::Label::new(tr("Rating Colors")));
+                        if cmap_selector(ui, &mut settings.cmap, &settings.custom_cmaps) {
+                            *app_state = State::CustomColors;
+                        }
+                        ui.end_row();
 
-        ui.vertical_centered(|ui| {
-            // Color map preview
-            ui.add_space(20.0);
-            let mut rating_text = egui::text::LayoutJob::default();
-
-            rating_text.append(
-                "SA Rating",
-                0.0,
-                TextFormat {
-                    font_id: FontId::proportional(18.0),
-                    color: cmap.get_sa_true(),
-                    ..Default::default()
-                },
-            );
+                        // Format of the mission timer in the main window and overlay
+                        ui.add(egui::Label::new(tr("Timer Format")));
+                        time_format_selector(ui, &mut settings.time_format);
+                        ui.end_row();
 
-            rating_text.append(
-                "Other Rating",
-                25.0,
-                TextFormat {
-                    font_id: FontId::proportional(18.0),
-                    color: cmap.get_sa_false(),
-                    ..Default::default()
-                },
-            );
+                        // Real time next to the in-game time
+                        ui.add(egui::Label::new(tr("Real Time")));
+                        ui.checkbox(&mut settings.show_rta, tr("Show"));
+                        ui.end_row();
 
-            ui.label(rating_text);
+                        // Track consecutive missions as a full game run
+                        ui.add(egui::Label::new(tr("Campaign Run")));
+                        ui.checkbox(&mut settings.campaign_mode, tr("Enable"));
+                        ui.end_row();
 
-            // About section
-            ui.add_space(35.0);
-            ui.hyperlink_to("GitHub", "https://github.com/Attrup/StatTracker");
-            ui.label(format!("Hitman StatTracker v{}", env!("CARGO_PKG_VERSION")));
+                        // Difficulty of the records, it cannot be read from the game
+                        ui.add(egui::Label::new(tr("Difficulty")));
+                        difficulty_selector(ui, &mut settings.difficulty, difficulties);
+                        ui.end_row();
+
+                        // Import and export of LiveSplit split files
+                        ui.add(egui::Label::new(tr("LiveSplit")));
+                        if ui.button(tr("Configure")).clicked() {
+                            *app_state = State::SplitFiles;
+                        }
+                        ui.end_row();
+
+                        // Export of the stored mission attempts
+                        ui.add(egui::Label::new(tr("History")));
+                        if ui.button(tr("Configure")).clicked() {
+                            *app_state = State::History;
+                        }
+                        ui.end_row();
+
+                        // Global hotkeys for use while the game is focused
+                        ui.add(egui::Label::new(tr("Hotkeys")));
+                        if ui.button(tr("Configure")).clicked() {
+                            *app_state = State::Hotkeys;
+                        }
+                        ui.end_row();
+
+                        // Aggregates of the stored mission attempts
+                        ui.add(egui::Label::new(tr("Statistics")));
+                        if ui.button(tr("Open")).clicked() {
+                            *app_state = State::Statistics;
+                        }
+                        ui.end_row();
+
+                        // Use game overlay
+                        ui.add(egui::Label::new(tr("Game Overlay")));
+                        ui.checkbox(&mut settings.show_overlay, tr("Enable"));
+                        ui.end_row();
+
+                        // Text size of the overlay
+                        ui.add(egui::Label::new(tr("Overlay Size")));
+
+                        ui.add(egui::Slider::new(&mut settings.overlay_size, 1..=10));
+                        ui.end_row();
+
+                        // Arrangement of the main window
+                        ui.add(egui::Label::new(tr("Window Layout")));
+                        if ui.button(tr("Configure")).clicked() {
+                            *app_state = State::Layout;
+                        }
+                        ui.end_row();
+
+                        // Content and placement of the overlay
+                        ui.add(egui::Label::new(tr("Overlay Options")));
+                        if ui.button(tr("Configure")).clicked() {
+                            *app_state = State::OverlaySettings;
+                        }
+                        ui.end_row();
+
+                        // Detail of the log file used for troubleshooting
+                        ui.add(egui::Label::new(tr("Log Level")));
+                        log_level_selector(ui, &mut settings.log_level);
+                    });
+            });
+
+        ui.vertical_centered(|ui| {
+            // Color map preview
+            ui.add_space(12.0);
+            rating_preview(ui, &settings.cmap);
+
+            // About section
+            ui.add_space(10.0);
+            ui.hyperlink_to("GitHub", "https://github.com/Attrup/StatTracker");
+            ui.label(format!("Hitman StatTracker v{}", env!("CARGO_PKG_VERSION")));
             ui.label("By Jonas Attrup");
 
             // Return to previous state button
-            ui.add_space(25.0);
+            ui.add_space(15.0);
             if ui
-                .button(egui::RichText::new("Exit Settings").size(15.0))
+                .button(egui::RichText::new(tr("Exit Settings")).size(15.0))
                 .clicked()
             {
                 *app_state = State::Waiting;
@@ -334,31 +1200,979 @@ fn display_settings(
     });
 }
 
-/// Display the current game stats in the defined order
-fn format_stats(ui: &mut Ui, stats: MissionStats) {
-    format_stat(ui, stats.shots_fired, "Shots Fired");
-    format_stat(ui, stats.close_encounters, "Close Encounters");
-    format_stat(ui, stats.headshots, "Headshots");
-    format_stat(ui, stats.alerts, "Alerts");
-    format_stat(ui, stats.enemies_killed, "Enemies Killed");
-    format_stat(ui, stats.enemies_harmed, "Enemies Harmed");
-    format_stat(ui, stats.innocents_killed, "Innocents Killed");
-    format_stat(ui, stats.innocents_harmed, "Innocents Harmed");
+/// Display the menu for importing and exporting LiveSplit split files
+fn display_split_files(
+    ctx: &egui::Context,
+    path: &mut String,
+    status: &mut String,
+    campaign: &CampaignRun,
+    difficulty: Difficulty,
+    records: &mut Records,
+    app_state: &mut State,
+) {
+    let Records {
+        best_times,
+        split_targets,
+    } = records;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("LiveSplit")).size(20.0));
+            ui.separator();
+        });
+
+        egui::Grid::new("SplitFiles")
+            .num_columns(2)
+            .spacing([25.0, 5.0])
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(tr("Split File")));
+                ui.add(egui::TextEdit::singleline(path).desired_width(160.0));
+                ui.end_row();
+
+                // Targets and best times are kept for each difficulty
+                ui.add(egui::Label::new(tr("Difficulty")));
+                ui.label(tr(difficulty.get_label()));
+                ui.end_row();
+
+                ui.add(egui::Label::new(tr("Targets")));
+                ui.label(tr_args("{} missions", &[&split_targets.len()]));
+                ui.end_row();
+            });
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                // Personal best becomes the targets, best segments are merged into the best times
+                if ui.button(tr("Import")).clicked() {
+                    *status = match livesplit::import_lss(Path::new(path)) {
+                        Ok(imported) => {
+                            for (code, time) in imported.best_segments {
+                                let best = best_times.entry(code).or_insert(time);
+                                *best = best.min(time);
+                            }
+                            *split_targets = imported.personal_best;
+
+                            match imported.unmatched.len() {
+                                0 => tr_args("Imported {} targets", &[&split_targets.len()]),
+                                n => tr_args(
+                                    "Imported {} targets, {} unknown splits",
+                                    &[&split_targets.len(), &n],
+                                ),
+                            }
+                        }
+                        Err(err) => {
+                            warn!(%err, path = path.as_str(), "Failed to import split file");
+                            tr_args("Import failed: {}", &[&err])
+                        }
+                    };
+                }
+
+                // The current campaign run is exported as the personal best
+                if ui.button(tr("Export Run")).clicked() {
+                    *status = match livesplit::export_lss(Path::new(path), campaign, best_times) {
+                        Ok(()) => tr("Exported campaign run").to_string(),
+                        Err(err) => {
+                            warn!(%err, path = path.as_str(), "Failed to export split file");
+                            tr_args("Export failed: {}", &[&err])
+                        }
+                    };
+                }
+
+                if ui.button(tr("Clear Targets")).clicked() {
+                    split_targets.clear();
+                    status.clear();
+                }
+            });
+
+            ui.add_space(4.0);
+            ui.label(status.as_str());
+
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Display the menu for exporting and clearing the stored mission attempts
+fn display_history(
+    ctx: &egui::Context,
+    history: &mut MissionHistory,
+    path: &mut String,
+    status: &mut String,
+    app_state: &mut State,
+) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("History")).size(20.0));
+            ui.separator();
+        });
+
+        egui::Grid::new("History")
+            .num_columns(2)
+            .spacing([25.0, 5.0])
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(tr("Attempts")));
+                ui.label(history.attempts().len().to_string());
+                ui.end_row();
+
+                // The file extension is added based on the export format
+                ui.add(egui::Label::new(tr("Export File")));
+                ui.add(egui::TextEdit::singleline(path).desired_width(160.0));
+                ui.end_row();
+            });
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let base_path = Path::new(path.as_str());
+
+                if ui.button(tr("Export CSV")).clicked() {
+                    let file = base_path.with_extension("csv");
+                    *status = match history.export_csv(&file) {
+                        Ok(()) => tr_args("Exported to {}", &[&file.display()]),
+                        Err(err) => {
+                            warn!(%err, path = %file.display(), "Failed to export history");
+                            tr_args("Export failed: {}", &[&err])
+                        }
+                    };
+                }
+
+                if ui.button(tr("Export JSON")).clicked() {
+                    let file = base_path.with_extension("json");
+                    *status = match history.export_json(&file) {
+                        Ok(()) => tr_args("Exported to {}", &[&file.display()]),
+                        Err(err) => {
+                            warn!(%err, path = %file.display(), "Failed to export history");
+                            tr_args("Export failed: {}", &[&err])
+                        }
+                    };
+                }
+
+                if ui.button(tr("Clear")).clicked() {
+                    history.clear();
+                    status.clear();
+                }
+            });
+
+            ui.add_space(4.0);
+            ui.label(status.as_str());
+
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Display the statistics of the stored attempts of a single mission
+fn display_statistics(
+    ctx: &egui::Context,
+    history: &MissionHistory,
+    mission: &mut String,
+    difficulty: &mut Difficulty,
+    time_format: &TimeFormat,
+    app_state: &mut State,
+) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("Statistics")).size(20.0));
+            ui.separator();
+        });
+
+        // Attempts are summarized separately for each difficulty
+        ui.horizontal(|ui| {
+            ui.label(tr("Difficulty"));
+            difficulty_selector(ui, difficulty, &Difficulty::all_difficulties());
+        });
+
+        let missions = played_missions(history.attempts(), *difficulty);
+
+        if missions.is_empty() {
+            ui.vertical_centered(|ui| ui.label(tr("No stored attempts")));
+        } else {
+            // Default to the first played mission
+            if !missions.contains(mission) {
+                *mission = missions[0].clone();
+            }
+
+            // Attempts store the English mission name, translated by the mission code
+            let display_name = |code: &str| {
+                let name = history
+                    .attempts()
+                    .iter()
+                    .find(|attempt| attempt.mission_code == code)
+                    .map_or(code, |attempt| attempt.mission_name.as_str());
+                mission_name(Some(code), name).to_string()
+            };
+
+            egui::ComboBox::from_id_source("StatisticsMission")
+                .selected_text(display_name(mission))
+                .width(ui.available_width())
+                .show_ui(ui, |ui| {
+                    for option in missions {
+                        let label = display_name(&option);
+                        ui.selectable_value(mission, option, label);
+                    }
+                });
+
+            let summary = MissionSummary::new(history.attempts(), mission, *difficulty);
+            let format_time = |time: Option<f32>| {
+                time.map_or(String::from("-"), |time| time_format.format_ticks(time))
+            };
+
+            ui.add_space(4.0);
+            egui::Grid::new("Statistics")
+                .num_columns(2)
+                .spacing([25.0, 3.0])
+                .show(ui, |ui| {
+                    ui.add(egui::Label::new(tr("Attempts")));
+                    ui.label(summary.attempts.to_string());
+                    ui.end_row();
+
+                    ui.add(egui::Label::new(tr("SA Rate")));
+                    ui.label(summary.sa_rate().map_or(String::from("-"), |rate| {
+                        format!(
+                            "{:.0}% ({}/{})",
+                            rate * 100.0,
+                            summary.sa_attempts,
+                            summary.rated_attempts
+                        )
+                    }));
+                    ui.end_row();
+
+                    ui.add(egui::Label::new(tr("Best Time")))
+                        .on_hover_text(tr("Only completed missions are timed"));
+                    ui.monospace(format_time(summary.best_time));
+                    ui.end_row();
+
+                    ui.add(egui::Label::new(tr("Median Time")))
+                        .on_hover_text(tr("Only completed missions are timed"));
+                    ui.monospace(format_time(summary.median_time));
+                    ui.end_row();
+
+                    ui.add(egui::Label::new(tr("SA Lost By")));
+                    ui.label(summary.sa_breaker.map_or("-", |i| tr(STAT_NAMES[i])));
+                    ui.end_row();
+                });
+
+            ui.add_space(4.0);
+            ui.label(tr("Best Time per Session"));
+            trend_plot(ui, &summary.session_best, time_format);
+        }
+
+        ui.vertical_centered(|ui| {
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Display the menu for binding the global hotkeys
+fn display_hotkeys(
+    ctx: &egui::Context,
+    settings: &mut HotkeySettings,
+    recording: &mut Option<HotkeyAction>,
+    status: &str,
+    app_state: &mut State,
+) {
+    // Bind the next pressed key combination, or cancel with escape
+    if let Some(action) = *recording {
+        let pressed = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some(Hotkey::new(
+                    *key,
+                    modifiers.ctrl,
+                    modifiers.shift,
+                    modifiers.alt,
+                )),
+                _ => None,
+            })
+        });
+
+        if let Some(hotkey) = pressed {
+            if hotkey.key != Key::Escape {
+                settings.bindings.insert(action, hotkey);
+            }
+            *recording = None;
+        }
+    }
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("Hotkeys")).size(20.0));
+            ui.separator();
+        });
+
+        egui::Grid::new("Hotkeys")
+            .num_columns(2)
+            .spacing([25.0, 5.0])
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(tr("Hotkeys")));
+                ui.checkbox(&mut settings.enabled, tr("Enable"));
+                ui.end_row();
+
+                for action in HotkeyAction::all_actions() {
+                    let name = ui.add(egui::Label::new(tr(action.get_label())));
+                    if let Some(description) = action.get_description() {
+                        name.on_hover_text(tr(description));
+                    }
+                    ui.horizontal(|ui| {
+                        let label = if *recording == Some(action) {
+                            tr("Press a key").to_string()
+                        } else {
+                            settings
+                                .bindings
+                                .get(&action)
+                                .map_or(tr("Unbound").to_string(), |hotkey| hotkey.get_label())
+                        };
+
+                        if ui.button(label).clicked() {
+                            *recording = Some(action);
+                        }
+                        if ui.small_button(tr("Clear")).clicked() {
+                            settings.bindings.remove(&action);
+                        }
+                    });
+                    ui.end_row();
+                }
+
+                ui.add(egui::Label::new(tr("Marker Log")));
+                ui.add(egui::TextEdit::singleline(&mut settings.marker_log).desired_width(120.0));
+                ui.end_row();
+            });
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(4.0);
+            ui.label(status);
+
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *recording = None;
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Display the menu for the main window layout and the order and visibility of the stats
+fn display_layout_settings(
+    ctx: &egui::Context,
+    layout: &mut LayoutSettings,
+    app_state: &mut State,
+) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("Window Layout")).size(20.0));
+            ui.separator();
+        });
+
+        egui::Grid::new("Layout")
+            .num_columns(2)
+            .spacing([25.0, 5.0])
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(tr("Preset")));
+                preset_selector(ui, &mut layout.preset);
+                ui.end_row();
+
+                // Scale the text with the window size relative to the preset size
+                ui.add(egui::Label::new(tr("Scale Text")));
+                ui.checkbox(&mut layout.scale_text, tr("Enable"));
+                ui.end_row();
+            });
+
+        // Visibility and order of the stats
+        ui.add_space(8.0);
+        ui.label(tr("Stats"));
+        egui::ScrollArea::vertical()
+            .max_height(160.0)
+            .show(ui, |ui| {
+                let mut moved = None;
+
+                for (position, stat) in layout.stat_order.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(position > 0, egui::Button::new(tr("Up")).small())
+                            .clicked()
+                        {
+                            moved = Some(position);
+                        }
+                        if let Some(show) = layout.show_stats.get_mut(*stat) {
+                            ui.checkbox(show, tr(STAT_NAMES[*stat]));
+                        }
+                    });
+                }
+
+                if let Some(position) = moved {
+                    layout.move_stat_up(position);
+                }
+            });
+
+        ui.vertical_centered(|ui| {
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Display the menu for saving, switching, exporting and importing profiles.
+/// Returns the settings of the profile to switch to, if one was loaded or imported.
+fn display_profiles(
+    ctx: &egui::Context,
+    settings: &mut Settings,
+    name: &mut String,
+    status: &mut String,
+    app_state: &mut State,
+) -> Option<ProfileSettings> {
+    let mut selected = None;
+    let current = settings.profile();
+    let Settings {
+        profiles,
+        active_profile: active,
+        profile_path: path,
+        ..
+    } = settings;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("Profiles")).size(20.0));
+            ui.separator();
+        });
+
+        egui::Grid::new("Profiles")
+            .num_columns(2)
+            .spacing([25.0, 5.0])
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(tr("Active")));
+                ui.label(active.as_str());
+                ui.end_row();
+
+                // Save the current settings, replacing any profile with the same name
+                ui.add(egui::TextEdit::singleline(name).desired_width(120.0));
+                if ui
+                    .add_enabled(!name.trim().is_empty(), egui::Button::new(tr("Save")))
+                    .clicked()
+                {
+                    let profile = Profile {
+                        name: name.trim().to_string(),
+                        settings: current.clone(),
+                    };
+                    *active = profile.name.clone();
+                    store_profile(profiles, profile);
+                }
+                ui.end_row();
+
+                ui.add(egui::TextEdit::singleline(path).desired_width(120.0));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Export")).clicked() {
+                        let profile = Profile {
+                            name: active.clone(),
+                            settings: current.clone(),
+                        };
+                        *status = match profile.export(Path::new(path)) {
+                            Ok(()) => tr_args("Exported to {}", &[&path]),
+                            Err(err) => {
+                                warn!(%err, path = path.as_str(), "Failed to export profile");
+                                tr_args("Export failed: {}", &[&err])
+                            }
+                        };
+                    }
+
+                    // Imported profiles are saved and switched to immediately
+                    if ui.button(tr("Import")).clicked() {
+                        *status = match Profile::import(Path::new(path)) {
+                            Ok(profile) => {
+                                let message = tr_args("Imported {}", &[&profile.name]);
+                                *active = profile.name.clone();
+                                selected = Some(profile.settings.clone());
+                                store_profile(profiles, profile);
+                                message
+                            }
+                            Err(err) => {
+                                warn!(%err, path = path.as_str(), "Failed to import profile");
+                                tr_args("Import failed: {}", &[&err])
+                            }
+                        };
+                    }
+                });
+                ui.end_row();
+            });
+
+        ui.add_space(4.0);
+        ui.label(status.as_str());
+
+        // List of saved profiles
+        ui.add_space(4.0);
+        ui.label(tr("Saved Profiles"));
+        egui::ScrollArea::vertical()
+            .max_height(100.0)
+            .show(ui, |ui| {
+                let mut removed = None;
+                let mut loaded = None;
+
+                for (i, profile) in profiles.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Delete")).clicked() {
+                            removed = Some(i);
+                        }
+                        if ui.button(tr("Load")).clicked() {
+                            loaded = Some(i);
+                        }
+                        ui.label(&profile.name);
+                    });
+                }
+
+                // Keep the changes made to the active profile before switching
+                if let Some(i) = loaded {
+                    let profile = profiles[i].clone();
+                    if let Some(previous) = profiles.iter_mut().find(|p| p.name == *active) {
+                        previous.settings = current.clone();
+                    }
+                    *active = profile.name.clone();
+                    *name = profile.name;
+                    selected = Some(profile.settings);
+                }
+
+                if let Some(i) = removed {
+                    profiles.remove(i);
+                }
+            });
+
+        ui.vertical_centered(|ui| {
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *app_state = State::Settings;
+            }
+        });
+    });
+
+    selected
+}
+
+/// Display the list of running game processes, where the process to read the data from
+/// is selected. A pinned process is also used after the settings are left.
+fn display_processes(
+    ctx: &egui::Context,
+    sys: &mut System,
+    processes: &mut Vec<GameProcess>,
+    attached: &mut Option<GameProcess>,
+    pin: &mut ProcessPin,
+    app_state: &mut State,
+) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("Game Processes")).size(20.0));
+            ui.separator();
+        });
+
+        egui::Grid::new("ProcessPin")
+            .num_columns(2)
+            .spacing([25.0, 5.0])
+            .show(ui, |ui| {
+                // Only the pinned process, or instances of the pinned game, are attached to
+                ui.add(egui::Label::new(tr("Pinned")));
+                ui.horizontal(|ui| {
+                    let pinned = match (pin.pid, pin.game) {
+                        (Some(pid), _) => tr_args("PID {}", &[&pid]),
+                        (None, Some(game)) => game.get_label().to_uppercase(),
+                        (None, None) => tr("None").to_string(),
+                    };
+                    ui.label(pinned);
+
+                    if pin.is_set() && ui.button(tr("Unpin")).clicked() {
+                        *pin = ProcessPin::default();
+                    }
+                });
+                ui.end_row();
+
+                // Processes are only listed when the page is opened, as listing is slow
+                ui.add(egui::Label::new(tr("Processes")));
+                if ui.button(tr("Refresh")).clicked() {
+                    *processes = find_games(sys);
+                }
+                ui.end_row();
+            });
+
+        ui.add_space(4.0);
+        egui::ScrollArea::vertical()
+            .max_height(150.0)
+            .show(ui, |ui| {
+                if processes.is_empty() {
+                    ui.label(tr("No game is running"));
+                }
+
+                for process in processes.iter() {
+                    ui.separator();
+                    ui.label(process_label(process));
+                    ui.horizontal(|ui| {
+                        let is_attached = attached.as_ref() == Some(process);
+                        if ui
+                            .add_enabled(!is_attached, egui::Button::new(tr("Attach")))
+                            .clicked()
+                        {
+                            *attached = Some(process.clone());
+                        }
+
+                        if ui.button(tr("Pin")).clicked() {
+                            *attached = Some(process.clone());
+                            *pin = ProcessPin {
+                                game: Some(process.game),
+                                pid: Some(process.pid),
+                            };
+                        }
+
+                        if is_attached {
+                            ui.label(tr("Attached"));
+                        }
+                    });
+                }
+            });
+
+        ui.vertical_centered(|ui| {
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Add a profile, replacing any profile with the same name
+fn store_profile(profiles: &mut Vec<Profile>, profile: Profile) {
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+}
+
+/// Display the overlay settings menu where the overlay content, position and style are selected
+fn display_overlay_settings(
+    ctx: &egui::Context,
+    settings: &mut OverlaySettings,
+    app_state: &mut State,
+) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("Overlay Settings")).size(20.0));
+            ui.separator();
+        });
+
+        egui::ScrollArea::vertical()
+            .max_height(235.0)
+            .show(ui, |ui| {
+                egui::CollapsingHeader::new(tr("Content"))
+                    .default_open(true)
+                    .show(ui, |ui| overlay_content_settings(ui, &mut settings.content));
+
+                egui::CollapsingHeader::new(tr("Position")).show(ui, |ui| {
+                    overlay_position_settings(ui, &mut settings.position)
+                });
+
+                egui::CollapsingHeader::new(tr("Style"))
+                    .show(ui, |ui| overlay_style_settings(ui, &mut settings.style));
+
+                // The overlay is always hidden while the game is minimized
+                ui.add_space(4.0);
+                ui.checkbox(
+                    &mut settings.hide_unfocused,
+                    tr("Hide when game is unfocused"),
+                );
+            });
+
+        ui.vertical_centered(|ui| {
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                settings.position.edit_mode = false;
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Settings for the elements shown in the overlay
+fn overlay_content_settings(ui: &mut Ui, content: &mut OverlayContent) {
+    egui::Grid::new("OverlayContent")
+        .num_columns(2)
+        .spacing([25.0, 5.0])
+        .show(ui, |ui| {
+            // Layout selector
+            ui.add(egui::Label::new(tr("Layout")));
+            layout_selector(ui, &mut content.layout);
+            ui.end_row();
+
+            // Toggles for the general mission elements
+            ui.add(egui::Label::new(tr("Mission Name")));
+            ui.checkbox(&mut content.show_mission_name, tr("Show"));
+            ui.end_row();
+
+            ui.add(egui::Label::new(tr("Timer")));
+            ui.checkbox(&mut content.show_timer, tr("Show"));
+            ui.end_row();
+
+            ui.add(egui::Label::new(tr("Real Time")));
+            ui.checkbox(&mut content.show_rta, tr("Show"));
+            ui.end_row();
+
+            ui.add(egui::Label::new(tr("Rating Title")));
+            ui.checkbox(&mut content.show_rating, tr("Show"));
+            ui.end_row();
+        });
+
+    // Toggles for each of the stats
+    ui.add_space(8.0);
+    ui.label(tr("Stats"));
+    egui::Grid::new("OverlayStats")
+        .num_columns(2)
+        .spacing([10.0, 5.0])
+        .show(ui, |ui| {
+            for (i, name) in STAT_NAMES.iter().enumerate() {
+                ui.checkbox(&mut content.show_stats[i], tr(name));
+                if i % 2 == 1 {
+                    ui.end_row();
+                }
+            }
+        });
+}
+
+/// Settings for the placement of the overlay relative to the game window
+fn overlay_position_settings(ui: &mut Ui, position: &mut OverlayPosition) {
+    egui::Grid::new("OverlayPosition")
+        .num_columns(2)
+        .spacing([25.0, 5.0])
+        .show(ui, |ui| {
+            ui.add(egui::Label::new(tr("Anchor")));
+            anchor_selector(ui, &mut position.anchor);
+            ui.end_row();
+
+            ui.add(egui::Label::new(tr("Offset")));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut position.offset[0]).prefix("x: "));
+                ui.add(egui::DragValue::new(&mut position.offset[1]).prefix("y: "));
+            });
+            ui.end_row();
+
+            // Disables mouse passthrough so the overlay can be dragged
+            ui.add(egui::Label::new(tr("Position")));
+            ui.toggle_value(&mut position.edit_mode, tr("Drag to Move"));
+            ui.end_row();
+        });
+}
+
+/// Settings for the visual appearance of the overlay
+fn overlay_style_settings(ui: &mut Ui, style: &mut OverlayStyle) {
+    egui::Grid::new("OverlayStyle")
+        .num_columns(2)
+        .spacing([25.0, 5.0])
+        .show(ui, |ui| {
+            ui.add(egui::Label::new(tr("Font")));
+            font_selector(ui, &mut style.font);
+            ui.end_row();
+
+            // Colored text on a transparent background
+            ui.add(egui::Label::new(tr("Outline Only")));
+            ui.checkbox(&mut style.outline_only, tr("Enable"));
+            ui.end_row();
+
+            // Background and text color only apply when not in outline mode
+            ui.add_enabled(!style.outline_only, egui::Label::new(tr("Opacity")));
+            ui.add_enabled(
+                !style.outline_only,
+                egui::Slider::new(&mut style.opacity, 0.0..=1.0),
+            );
+            ui.end_row();
+
+            ui.add_enabled(!style.outline_only, egui::Label::new(tr("Text Color")));
+            ui.add_enabled_ui(!style.outline_only, |ui| {
+                ui.color_edit_button_srgba(&mut style.text_color);
+            });
+            ui.end_row();
+
+            ui.add(egui::Label::new(tr("Rounding")));
+            ui.add(egui::Slider::new(&mut style.rounding, 0.0..=20.0));
+            ui.end_row();
+        });
+}
+
+/// Display the editor for creating, selecting and deleting user defined color maps
+fn display_custom_colors(
+    ctx: &egui::Context,
+    editor: &mut ColorMapEditor,
+    cmap: &mut ColorMap,
+    custom_cmaps: &mut Vec<ColorMap>,
+    app_state: &mut State,
+) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            // Heading
+            ui.heading(egui::RichText::new(tr("Custom Colors")).size(20.0));
+            ui.separator();
+        });
+
+        egui::Grid::new("CustomColors")
+            .num_columns(2)
+            .spacing([25.0, 5.0])
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(tr("Name")));
+                ui.add(egui::TextEdit::singleline(&mut editor.label).desired_width(120.0));
+                ui.end_row();
+
+                ui.add(egui::Label::new(tr("SA Rating")));
+                ui.color_edit_button_srgba(&mut editor.sa_true);
+                ui.end_row();
+
+                ui.add(egui::Label::new(tr("SA At Risk")));
+                ui.color_edit_button_srgba(&mut editor.sa_risk);
+                ui.end_row();
+
+                ui.add(egui::Label::new(tr("Other Rating")));
+                ui.color_edit_button_srgba(&mut editor.sa_false);
+                ui.end_row();
+            });
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(8.0);
+            rating_preview(ui, &editor.to_cmap());
+
+            // Save the color map, replacing any user defined map with the same name
+            if ui
+                .add_enabled(editor.is_valid(), egui::Button::new(tr("Save and Select")))
+                .clicked()
+            {
+                let new_cmap = editor.to_cmap();
+                match custom_cmaps
+                    .iter_mut()
+                    .find(|map| map.get_label() == new_cmap.get_label())
+                {
+                    Some(existing) => *existing = new_cmap.clone(),
+                    None => custom_cmaps.push(new_cmap.clone()),
+                }
+                *cmap = new_cmap;
+            }
+        });
+
+        // List of saved color maps
+        ui.add_space(8.0);
+        ui.label(tr("Saved Colors"));
+        egui::ScrollArea::vertical()
+            .max_height(80.0)
+            .show(ui, |ui| {
+                let mut removed = None;
+
+                for (i, map) in custom_cmaps.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Delete")).clicked() {
+                            removed = Some(i);
+                        }
+                        if ui.button(tr("Edit")).clicked() {
+                            *editor = ColorMapEditor::from_cmap(map);
+                        }
+                        color_picker::show_color(ui, map.get_sa_true(), vec2(12.0, 12.0));
+                        color_picker::show_color(ui, map.get_sa_risk(), vec2(12.0, 12.0));
+                        color_picker::show_color(ui, map.get_sa_false(), vec2(12.0, 12.0));
+                        ui.label(map.get_label());
+                    });
+                }
+
+                if let Some(i) = removed {
+                    custom_cmaps.remove(i);
+                }
+            });
+
+        ui.vertical_centered(|ui| {
+            // Return to the main settings menu
+            ui.add_space(10.0);
+            if ui
+                .button(egui::RichText::new(tr("Back")).size(15.0))
+                .clicked()
+            {
+                *app_state = State::Settings;
+            }
+        });
+    });
+}
+
+/// Game data used to preview the overlay while no mission is running
+fn overlay_preview() -> GameData {
+    GameData::new(
+        None,
+        String::from("Mission Name"),
+        0.0,
+        Some(Rating::new(MissionStats::default(), SaRisk::Safe)),
+    )
+}
+
+/// Display the visible game stats in the order selected by the user
+fn format_stats(ui: &mut Ui, stats: MissionStats, layout: &LayoutSettings, scale: f32) {
+    let values = stats.to_array();
+    for i in layout.visible_stats() {
+        format_stat(ui, values[i], tr(STAT_NAMES[i]), 18.0 * scale);
+    }
 }
 
 /// Format the UI of a single stat
-fn format_stat(ui: &mut Ui, value: u32, name: &str) {
+fn format_stat(ui: &mut Ui, value: u32, name: &str, size: f32) {
     ui.label(
         egui::RichText::new(format!("{: >5}", value))
-            .size(18.0)
+            .size(size)
             .monospace(),
     );
-    ui.label(egui::RichText::new(name).size(18.0));
+    ui.label(egui::RichText::new(name).size(size));
     ui.end_row();
 }
 
 /// Creates two buttons that allow the user to switch between light and dark mode
-fn theme_toggle(ui: &mut Ui, ctx: &egui::Context, theme: &mut Visuals) {
+fn theme_toggle(ui: &mut Ui, ctx: &egui::Context, theme: &mut Theme) {
     let colors = if !ui.visuals().dark_mode {
         (Color32::LIGHT_BLUE, Color32::TRANSPARENT)
     } else {
@@ -368,31 +2182,283 @@ fn theme_toggle(ui: &mut Ui, ctx: &egui::Context, theme: &mut Visuals) {
     ui.horizontal(|ui| {
         // Set the theme to light mode
         if ui
-            .add(Button::new(egui::RichText::new("Light")).fill(colors.0))
+            .add(Button::new(egui::RichText::new(tr("Light"))).fill(colors.0))
             .clicked()
         {
-            ctx.set_visuals(Visuals::light());
-            *theme = Visuals::light();
+            *theme = Theme::Light;
+            ctx.set_visuals(theme.visuals());
         };
 
         // Set the theme to dark mode
         if ui
-            .add(Button::new(egui::RichText::new("Dark")).fill(colors.1))
+            .add(Button::new(egui::RichText::new(tr("Dark"))).fill(colors.1))
             .clicked()
         {
-            ctx.set_visuals(Visuals::dark());
-            *theme = Visuals::dark();
+            *theme = Theme::Dark;
+            ctx.set_visuals(theme.visuals());
         };
     });
 }
 
-/// Create color map dropdown menu
-fn cmap_selector(ui: &mut Ui, cmap: &mut ColorMap) {
+/// Create overlay layout dropdown menu
+fn layout_selector(ui: &mut Ui, layout: &mut OverlayLayout) {
+    egui::ComboBox::from_id_source("OverlayLayout")
+        .selected_text(tr(layout.get_label()))
+        .show_ui(ui, |ui| {
+            for option in OverlayLayout::all_layouts() {
+                ui.selectable_value(layout, option, tr(option.get_label()));
+            }
+        });
+}
+
+/// Create main window layout dropdown menu
+fn preset_selector(ui: &mut Ui, preset: &mut LayoutPreset) {
+    egui::ComboBox::from_id_source("LayoutPreset")
+        .selected_text(tr(preset.get_label()))
+        .show_ui(ui, |ui| {
+            for option in LayoutPreset::all_presets() {
+                ui.selectable_value(preset, option, tr(option.get_label()));
+            }
+        });
+}
+
+/// Create language dropdown menu, applying the selected language immediately
+fn language_selector(ui: &mut Ui, language: &mut Language) {
+    egui::ComboBox::from_id_source("Language")
+        .selected_text(language.get_label())
+        .show_ui(ui, |ui| {
+            for option in Language::all_languages() {
+                if ui
+                    .selectable_value(language, option, option.get_label())
+                    .changed()
+                {
+                    i18n::set_language(option);
+                }
+            }
+        });
+}
+
+/// Create difficulty dropdown menu with the given options
+fn difficulty_selector(ui: &mut Ui, difficulty: &mut Difficulty, options: &[Difficulty]) {
+    egui::ComboBox::from_id_source("Difficulty")
+        .selected_text(tr(difficulty.get_label()))
+        .show_ui(ui, |ui| {
+            for option in options {
+                ui.selectable_value(difficulty, *option, tr(option.get_label()));
+            }
+        });
+}
+
+/// Create log level dropdown menu
+fn log_level_selector(ui: &mut Ui, level: &mut LogLevel) {
+    egui::ComboBox::from_id_source("LogLevel")
+        .selected_text(level.get_label())
+        .show_ui(ui, |ui| {
+            for option in LogLevel::all_levels() {
+                if ui
+                    .selectable_value(level, option, option.get_label())
+                    .changed()
+                {
+                    logging::set_level(option);
+                }
+            }
+        });
+}
+
+/// Create timer format dropdown menu
+fn time_format_selector(ui: &mut Ui, time_format: &mut TimeFormat) {
+    egui::ComboBox::from_id_source("TimeFormat")
+        .selected_text(tr(time_format.get_label()))
+        .show_ui(ui, |ui| {
+            for option in TimeFormat::all_formats() {
+                ui.selectable_value(time_format, option, tr(option.get_label()));
+            }
+        });
+}
+
+/// Draw the stat increases of a mission as markers on a time axis, with one lane per stat
+/// and a vertical line where the SA rating was lost. Hovering a marker shows its details.
+fn timeline_plot(
+    ui: &mut Ui,
+    timeline: &MissionTimeline,
+    cmap: &ColorMap,
+    time_format: &TimeFormat,
+) {
+    let (rect, response) = ui.allocate_exact_size(vec2(ui.available_width(), 40.0), Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+
+    painter.rect_stroke(rect, 2.0, visuals.widgets.noninteractive.bg_stroke);
+
+    let plot = rect.shrink2(vec2(6.0, 4.0));
+    let duration = timeline.end_time().max(1.0);
+    let time_to_x = |time: f32| plot.left() + plot.width() * time / duration;
+
+    if let Some(time) = timeline.sa_lost() {
+        let stroke = Stroke::new(1.5, cmap.get_sa_false());
+        painter.vline(time_to_x(time), rect.y_range(), stroke);
+    }
+
+    let lane_height = plot.height() / STAT_NAMES.len() as f32;
+    let mut hovered = None;
+
+    for event in timeline.events() {
+        let center = pos2(
+            time_to_x(event.time),
+            plot.top() + lane_height * (event.stat as f32 + 0.5),
+        );
+        painter.circle_filled(center, 2.5, visuals.text_color());
+
+        if response
+            .hover_pos()
+            .is_some_and(|pos| (pos.x - center.x).abs() < 4.0)
+        {
+            hovered = Some(event);
+        }
+    }
+
+    painter.text(
+        rect.right_bottom() + vec2(-4.0, -2.0),
+        Align2::RIGHT_BOTTOM,
+        time_format.format_ticks(timeline.end_time()),
+        FontId::monospace(10.0),
+        visuals.weak_text_color(),
+    );
+
+    if let Some(event) = hovered {
+        response.on_hover_text(tr_args(
+            "{} +{} at {}",
+            &[
+                &tr(STAT_NAMES[event.stat]),
+                &event.increase,
+                &time_format.format_ticks(event.time),
+            ],
+        ));
+    }
+}
+
+/// Draw a line plot of mission times in ticks, with faster times towards the top
+fn trend_plot(ui: &mut Ui, times: &[f32], time_format: &TimeFormat) {
+    let (rect, _) = ui.allocate_exact_size(vec2(ui.available_width(), 70.0), Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+
+    painter.rect_stroke(rect, 2.0, visuals.widgets.noninteractive.bg_stroke);
+
+    let fastest = times.iter().copied().reduce(f32::min);
+    let slowest = times.iter().copied().reduce(f32::max);
+    let (Some(fastest), Some(slowest)) = (fastest, slowest) else {
+        return;
+    };
+
+    // Leave room for the time labels at the top and bottom
+    let plot = rect.shrink2(vec2(8.0, 14.0));
+    let range = (slowest - fastest).max(1.0);
+
+    let points: Vec<Pos2> = times
+        .iter()
+        .enumerate()
+        .map(|(i, time)| {
+            let x = if times.len() > 1 {
+                plot.left() + plot.width() * i as f32 / (times.len() - 1) as f32
+            } else {
+                plot.center().x
+            };
+            pos2(x, plot.top() + plot.height() * (time - fastest) / range)
+        })
+        .collect();
+
+    let color = visuals.selection.bg_fill;
+    painter.add(Shape::line(points.clone(), Stroke::new(1.5, color)));
+    for point in points {
+        painter.circle_filled(point, 2.5, color);
+    }
+
+    for (time, anchor, pos) in [
+        (
+            fastest,
+            Align2::RIGHT_TOP,
+            rect.right_top() + vec2(-4.0, 2.0),
+        ),
+        (
+            slowest,
+            Align2::RIGHT_BOTTOM,
+            rect.right_bottom() + vec2(-4.0, -2.0),
+        ),
+    ] {
+        painter.text(
+            pos,
+            anchor,
+            time_format.format_ticks(time),
+            FontId::monospace(10.0),
+            visuals.weak_text_color(),
+        );
+    }
+}
+
+/// Create overlay font dropdown menu
+fn font_selector(ui: &mut Ui, font: &mut OverlayFont) {
+    egui::ComboBox::from_id_source("OverlayFont")
+        .selected_text(font.get_label())
+        .show_ui(ui, |ui| {
+            for option in OverlayFont::all_fonts() {
+                ui.selectable_value(font, option, option.get_label());
+            }
+        });
+}
+
+/// Create overlay anchor dropdown menu
+fn anchor_selector(ui: &mut Ui, anchor: &mut OverlayAnchor) {
+    egui::ComboBox::from_id_source("OverlayAnchor")
+        .selected_text(tr(anchor.get_label()))
+        .show_ui(ui, |ui| {
+            for option in OverlayAnchor::all_anchors() {
+                ui.selectable_value(anchor, option, tr(option.get_label()));
+            }
+        });
+}
+
+/// Create color map dropdown menu with the presets followed by the user defined maps
+/// Returns true if the custom color map editor was requested
+fn cmap_selector(ui: &mut Ui, cmap: &mut ColorMap, custom_cmaps: &[ColorMap]) -> bool {
+    let mut open_editor = false;
+
     egui::ComboBox::from_label("")
         .selected_text(format!("{}", cmap.get_label()))
         .show_ui(ui, |ui| {
-            for map in ColorMap::all_cmaps() {
+            for map in ColorMap::all_cmaps().iter().chain(custom_cmaps) {
                 ui.selectable_value(cmap, map.clone(), map.get_label());
             }
+
+            ui.separator();
+            open_editor = ui.selectable_label(false, "Custom...").clicked();
         });
+
+    open_editor
+}
+
+/// Preview the colors of each SA risk level side by side
+fn rating_preview(ui: &mut Ui, cmap: &ColorMap) {
+    let mut rating_text = egui::text::LayoutJob::default();
+
+    for (i, (label, risk)) in [
+        ("SA Rating", SaRisk::Safe),
+        ("At Risk", SaRisk::AtRisk),
+        ("Lost", SaRisk::Lost),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        rating_text.append(
+            tr(label),
+            if i == 0 { 0.0 } else { 20.0 },
+            TextFormat {
+                font_id: FontId::proportional(16.0),
+                color: cmap.get_rating_color(risk),
+                ..Default::default()
+            },
+        );
+    }
+
+    ui.label(rating_text);
 }
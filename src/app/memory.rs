@@ -1,8 +1,20 @@
 use read_process_memory::{copy_address, ProcessHandle};
 use std::io;
+use tracing::{trace, warn};
 
 /// Read n bytes from memory, potentially following higher level pointers
 pub fn read_memory(address: usize, pid: u32, bytes: usize, offsets: Vec<usize>) -> io::Result<Vec<u8>> {
+    let result = read_pointer_chain(address, pid, bytes, &offsets);
+
+    // Reads fail regularly while the game is loading, so failures are only traced
+    if let Err(err) = &result {
+        trace!(pid, address = format_args!("{address:#X}"), ?offsets, %err, "Memory read failed");
+    }
+
+    result
+}
+
+fn read_pointer_chain(address: usize, pid: u32, bytes: usize, offsets: &[usize]) -> io::Result<Vec<u8>> {
     let handle: ProcessHandle = pid.try_into()?;
 
     let mut pointer = address;
@@ -26,10 +38,14 @@ pub fn decode_to_u32(bytes: io::Result<Vec<u8>>) -> Option<u32> {
             if let Ok(value) = bytes.try_into() {
                 Some(u32::from_le_bytes(value))
             } else {
+                warn!("Memory read returned the wrong number of bytes for a u32");
                 None
             }
         }
-        Err(_) => None,
+        Err(err) => {
+            trace!(%err, "Failed to read u32 value");
+            None
+        }
     }
 }
 
@@ -40,10 +56,14 @@ pub fn decode_to_f32(bytes: io::Result<Vec<u8>>) -> Option<f32> {
             if let Ok(value) = bytes.try_into() {
                 Some(f32::from_le_bytes(value))
             } else {
+                warn!("Memory read returned the wrong number of bytes for a f32");
                 None
             }
         }
-        Err(_) => None,
+        Err(err) => {
+            trace!(%err, "Failed to read f32 value");
+            None
+        }
     }
 }
 
@@ -51,6 +71,9 @@ pub fn decode_to_f32(bytes: io::Result<Vec<u8>>) -> Option<f32> {
 pub fn decode_to_string(bytes: Vec<u8>) -> Option<String> {
     match String::from_utf8(bytes) {
         Ok(string) => Some(string),
-        Err(_) => None,
+        Err(err) => {
+            trace!(%err, "Memory read is not a valid string");
+            None
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,68 +1,587 @@
-use crate::Window;
+use crate::{GameData, SaRisk, Window, STAT_NAMES};
 
-use super::colors::ColorMap;
+use super::i18n::{mission_name, tr};
+use super::{colors::ColorMap, fonts::OverlayFont, time_format::TimeFormat};
+use egui::Color32;
 
-const OVERLAY_WIDTH_MULTIPLIER: u8 = 22;
-const OVERLAY_HEIGHT_MULTIPLIER: u8 = 9;
+const OVERLAY_PADDING_MULTIPLIER: u8 = 2;
 const OVERLAY_TEXT_SIZE_MULTIPLIER: u8 = 8;
 const WINDOW_FRAME_THICKNESS: i32 = 2;
 
+/// Available layouts for arranging the elements of the overlay
+#[derive(PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
+pub enum OverlayLayout {
+    SingleLine,
+    Stacked,
+    Grid,
+}
+
+impl OverlayLayout {
+    pub fn all_layouts() -> [Self; 3] {
+        [
+            OverlayLayout::SingleLine,
+            OverlayLayout::Stacked,
+            OverlayLayout::Grid,
+        ]
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            OverlayLayout::SingleLine => "Single Line",
+            OverlayLayout::Stacked => "Stacked",
+            OverlayLayout::Grid => "Grid",
+        }
+    }
+}
+
+/// User selection of the elements displayed in the overlay
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct OverlayContent {
+    pub layout: OverlayLayout,
+    pub show_timer: bool,
+    pub show_rta: bool,
+    pub show_mission_name: bool,
+    pub show_rating: bool,
+    pub show_stats: [bool; 8],
+}
+
+impl Default for OverlayContent {
+    fn default() -> Self {
+        OverlayContent {
+            layout: OverlayLayout::SingleLine,
+            show_timer: true,
+            show_rta: false,
+            show_mission_name: false,
+            show_rating: false,
+            show_stats: [false; 8],
+        }
+    }
+}
+
+impl OverlayContent {
+    /// Arrange the enabled elements into the rows of text shown in the overlay
+    fn rows(&self, data: &GameData, time_format: &TimeFormat, rta: f32) -> Vec<String> {
+        let mut elements = Vec::new();
+
+        if self.show_mission_name {
+            let name = mission_name(data.mission_code.as_deref(), &data.mission_name);
+            elements.push(name.to_string());
+        }
+
+        if self.show_timer {
+            elements.push(time_format.format_ticks(data.mission_time));
+        }
+
+        if self.show_rta {
+            elements.push(format!("RTA {}", time_format.format_seconds(rta)));
+        }
+
+        if self.show_rating && data.rating.is_some() {
+            elements.push(tr("SILENT ASSASSIN").to_string());
+        }
+
+        let stats = data.rating.map(|r| r.stats).unwrap_or_default().to_array();
+        let enabled_stats = (0..stats.len()).filter(|&i| self.show_stats[i]);
+
+        match self.layout {
+            OverlayLayout::SingleLine => {
+                elements
+                    .extend(enabled_stats.map(|i| format!("{} {}", tr(STAT_NAMES[i]), stats[i])));
+                if elements.is_empty() {
+                    elements
+                } else {
+                    vec![elements.join("  ")]
+                }
+            }
+            OverlayLayout::Stacked => {
+                elements.extend(
+                    enabled_stats.map(|i| format!("{: >3} {}", stats[i], tr(STAT_NAMES[i]))),
+                );
+                elements
+            }
+            OverlayLayout::Grid => {
+                // Stats are placed two per row in cells as wide as the longest translated
+                // name to align the columns
+                let enabled_stats = enabled_stats.collect::<Vec<usize>>();
+                let width = enabled_stats
+                    .iter()
+                    .map(|&i| tr(STAT_NAMES[i]).chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let cells = enabled_stats
+                    .iter()
+                    .map(|&i| format!("{: >3} {: <width$}", stats[i], tr(STAT_NAMES[i])))
+                    .collect::<Vec<String>>();
+
+                elements.extend(
+                    cells
+                        .chunks(2)
+                        .map(|row| row.join(" ").trim_end().to_string()),
+                );
+                elements
+            }
+        }
+    }
+}
+
+/// Points of the game window the overlay can be attached to
+#[derive(PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
+pub enum OverlayAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    Custom,
+}
+
+impl OverlayAnchor {
+    pub fn all_anchors() -> [Self; 7] {
+        [
+            OverlayAnchor::TopLeft,
+            OverlayAnchor::TopCenter,
+            OverlayAnchor::TopRight,
+            OverlayAnchor::BottomLeft,
+            OverlayAnchor::BottomCenter,
+            OverlayAnchor::BottomRight,
+            OverlayAnchor::Custom,
+        ]
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            OverlayAnchor::TopLeft => "Top Left",
+            OverlayAnchor::TopCenter => "Top Center",
+            OverlayAnchor::TopRight => "Top Right",
+            OverlayAnchor::BottomLeft => "Bottom Left",
+            OverlayAnchor::BottomCenter => "Bottom Center",
+            OverlayAnchor::BottomRight => "Bottom Right",
+            OverlayAnchor::Custom => "Custom",
+        }
+    }
+}
+
+/// Placement of the overlay relative to the game window
+/// The offset is given in physical pixels. For the custom anchor it is measured
+/// from the top left corner of the game window, otherwise from the anchor point.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct OverlayPosition {
+    pub anchor: OverlayAnchor,
+    pub offset: [i32; 2],
+
+    // Allows the overlay to be dragged into place (Not persistent)
+    #[serde(skip)]
+    pub edit_mode: bool,
+}
+
+impl Default for OverlayPosition {
+    fn default() -> Self {
+        OverlayPosition {
+            anchor: OverlayAnchor::TopCenter,
+            offset: [0, 0],
+            edit_mode: false,
+        }
+    }
+}
+
+impl OverlayPosition {
+    /// Calculate the top left corner of the overlay in physical pixels
+    fn resolve(&self, game_window: &Option<Window>, size: [f32; 2]) -> [f32; 2] {
+        // Default to the top left corner of the screen if no window is found
+        let (left, top, right, bottom) = match game_window {
+            Some(window) => (window.left, window.top, window.right, window.bottom),
+            None => (0, 0, size[0] as i32, size[1] as i32),
+        };
+
+        let (x, y) = match self.anchor {
+            OverlayAnchor::Custom => (left as f32, top as f32),
+            anchor => {
+                let x = match anchor {
+                    OverlayAnchor::TopLeft | OverlayAnchor::BottomLeft => {
+                        (left + WINDOW_FRAME_THICKNESS) as f32
+                    }
+                    OverlayAnchor::TopRight | OverlayAnchor::BottomRight => {
+                        (right - WINDOW_FRAME_THICKNESS) as f32 - size[0]
+                    }
+                    _ => (left + right) as f32 * 0.5 - size[0] * 0.5,
+                };
+
+                let y = match anchor {
+                    OverlayAnchor::BottomLeft
+                    | OverlayAnchor::BottomCenter
+                    | OverlayAnchor::BottomRight => {
+                        (bottom - WINDOW_FRAME_THICKNESS) as f32 - size[1]
+                    }
+                    _ => (top + WINDOW_FRAME_THICKNESS) as f32,
+                };
+
+                (x, y)
+            }
+        };
+
+        [x + self.offset[0] as f32, y + self.offset[1] as f32]
+    }
+
+    /// Store a position the overlay was dragged to as a custom offset
+    fn set_custom(&mut self, game_window: &Option<Window>, position: [f32; 2]) {
+        let origin = match game_window {
+            Some(window) => [window.left, window.top],
+            None => [0, 0],
+        };
+
+        self.anchor = OverlayAnchor::Custom;
+        self.offset = [
+            position[0].round() as i32 - origin[0],
+            position[1].round() as i32 - origin[1],
+        ];
+    }
+}
+
+/// Visual appearance of the overlay
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct OverlayStyle {
+    pub opacity: f32,
+    pub rounding: f32,
+    pub outline_only: bool,
+    pub text_color: Color32,
+    pub font: OverlayFont,
+}
+
+impl Default for OverlayStyle {
+    fn default() -> Self {
+        OverlayStyle {
+            opacity: 1.0,
+            rounding: 0.0,
+            outline_only: false,
+            text_color: Color32::WHITE,
+            font: OverlayFont::Monofonto,
+        }
+    }
+}
+
+/// All user settings of the overlay, except for the toggle and size shown in the main settings
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    pub content: OverlayContent,
+    pub position: OverlayPosition,
+    pub style: OverlayStyle,
+    pub hide_unfocused: bool,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn draw_overlay(
     ctx: &egui::Context,
     cmap: &ColorMap,
     game_window: &Option<Window>,
     overlay_size: &u8,
-    timer: &u32,
-    sa_status: &bool,
+    settings: &mut OverlaySettings,
+    time_format: &TimeFormat,
+    rta: f32,
+    data: &GameData,
 ) {
-    // Create colored background frame depending on the current SA status
+    let style = &settings.style;
+    let position = &mut settings.position;
+    let rating_color = cmap.get_rating_color(data.rating.map_or(SaRisk::Safe, |r| r.sa_risk));
+
+    // Create colored background frame depending on the current SA status. In outline
+    // mode the background is left transparent and the text is colored instead.
+    let (fill, text_color) = if style.outline_only {
+        (Color32::TRANSPARENT, rating_color)
+    } else {
+        (rating_color.gamma_multiply(style.opacity), style.text_color)
+    };
+
     let frame = egui::containers::Frame {
-        fill: cmap.get_rating_color(*sa_status),
+        fill,
+        rounding: egui::Rounding::same(style.rounding),
+        // Outline the overlay while it is being positioned
+        stroke: if position.edit_mode {
+            egui::Stroke::new(2.0, Color32::WHITE)
+        } else {
+            egui::Stroke::NONE
+        },
         ..Default::default()
     };
 
-    // Calculate the overlay position
-    let width = overlay_size * OVERLAY_WIDTH_MULTIPLIER;
-    let height = overlay_size * OVERLAY_HEIGHT_MULTIPLIER;
+    let rows = settings.content.rows(data, time_format, rta);
+    if rows.is_empty() {
+        return;
+    }
 
-    let overlay_position = match game_window {
-        Some(window) => {
-            [
-                (window.left + window.right - width as i32) as f32 * 0.5, // Center the overlay
-                (window.top + WINDOW_FRAME_THICKNESS) as f32,
-            ]
-        }
-        None => [0.0, 0.0], // Default to top left corner of the screen
-    };
+    // Calculate the overlay size from the laid out rows of text
+    let font_id = egui::FontId::new(
+        (overlay_size * OVERLAY_TEXT_SIZE_MULTIPLIER) as f32,
+        style.font.family(),
+    );
+
+    let text_size = ctx.fonts(|fonts| {
+        rows.iter()
+            .map(|row| {
+                fonts
+                    .layout_no_wrap(row.clone(), font_id.clone(), text_color)
+                    .size()
+            })
+            .fold(egui::Vec2::ZERO, |total, row| {
+                egui::vec2(total.x.max(row.x), total.y + row.y)
+            })
+    });
+
+    let width = (text_size.x + 2.0 * (overlay_size * OVERLAY_PADDING_MULTIPLIER) as f32).ceil();
+    let height = text_size.y.ceil();
+
+    // The game window is given in physical pixels while egui positions viewports in points.
+    // Use the scale factor of the monitor the overlay was last shown on, as the game
+    // may be running on a secondary monitor with a different DPI than the main window.
+    let scale_id = egui::Id::new("overlay_pixels_per_point");
+    let pixels_per_point = ctx
+        .data(|data| data.get_temp::<f32>(scale_id))
+        .unwrap_or(ctx.pixels_per_point());
+
+    let physical_position = position.resolve(
+        game_window,
+        [width * pixels_per_point, height * pixels_per_point],
+    );
+    let overlay_position = [
+        physical_position[0] / pixels_per_point,
+        physical_position[1] / pixels_per_point,
+    ];
 
-    ctx.show_viewport_immediate(
+    let (overlay_scale, dragged_to) = ctx.show_viewport_immediate(
         // Build new overlay viewport
         egui::ViewportId::from_hash_of("overlay"),
         egui::ViewportBuilder::default()
             .with_title("StatTracker Overlay")
-            .with_inner_size([width as f32, height as f32])
+            .with_inner_size([width, height])
             .with_always_on_top()
             .with_transparent(true)
             .with_resizable(false)
-            .with_mouse_passthrough(true)
+            .with_mouse_passthrough(!position.edit_mode)
             .with_decorations(false)
             .with_position(overlay_position),
         |ctx, _| {
-            // Draw the mission timer in the center of the overlay
+            // Draw each row of the overlay centered below each other
             egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 0.0;
                 ui.vertical_centered(|ui| {
-                    ui.label(
-                        egui::RichText::new(format!(
-                            "{:0>2}:{:0>2}",
-                            timer / 3600,
-                            (timer / 60) % 60
-                        ))
-                        .size((overlay_size * OVERLAY_TEXT_SIZE_MULTIPLIER) as f32)
-                        .monospace()
-                        .color(egui::Color32::WHITE),
-                    )
+                    for row in rows {
+                        ui.label(
+                            egui::RichText::new(row)
+                                .font(font_id.clone())
+                                .color(text_color),
+                        );
+                    }
                 });
+
+                // Let the window manager move the overlay while in edit mode
+                if position.edit_mode {
+                    let drag = ui.interact(
+                        ui.max_rect(),
+                        egui::Id::new("overlay_drag"),
+                        egui::Sense::drag(),
+                    );
+
+                    if drag.drag_started() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
+                    }
+                }
             });
+
+            // Report the scale factor and the current position of the overlay
+            let scale = ctx.pixels_per_point();
+            let dragged_to = ctx
+                .input(|i| i.viewport().outer_rect)
+                .map(|rect| [rect.min.x * scale, rect.min.y * scale]);
+
+            (scale, dragged_to)
         },
     );
+
+    // Store the position the overlay was dragged to. Changes to the requested position
+    // take a frame to be applied, so the window is only considered moved by the user
+    // if it is neither at the current nor at the previously requested position.
+    let requested_id = egui::Id::new("overlay_requested_position");
+    let previous_position = ctx
+        .data(|data| data.get_temp::<[f32; 2]>(requested_id))
+        .unwrap_or(physical_position);
+
+    if position.edit_mode {
+        if let Some(dragged_to) = dragged_to {
+            let differs =
+                |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).abs() >= 1.0 || (a[1] - b[1]).abs() >= 1.0;
+
+            if differs(dragged_to, physical_position) && differs(dragged_to, previous_position) {
+                position.set_custom(game_window, dragged_to);
+            }
+        }
+    }
+
+    ctx.data_mut(|data| {
+        data.insert_temp(scale_id, overlay_scale);
+        data.insert_temp(requested_id, physical_position);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_fixtures::menus;
+    use crate::{MissionStats, Rating};
+
+    fn data() -> GameData {
+        let stats = MissionStats {
+            shots_fired: 3,
+            enemies_killed: 12,
+            ..Default::default()
+        };
+        let rating = Rating::new(stats, SaRisk::Lost);
+        GameData::new(None, String::from("Miami"), 3600.0, Some(rating))
+    }
+
+    fn content(layout: OverlayLayout) -> OverlayContent {
+        let mut show_stats = [false; 8];
+        show_stats[0] = true;
+        show_stats[4] = true;
+
+        OverlayContent {
+            layout,
+            show_rta: true,
+            show_stats,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn single_line_joins_all_elements() {
+        let rows = content(OverlayLayout::SingleLine).rows(&data(), &TimeFormat::Minutes, 65.0);
+        assert_eq!(
+            rows,
+            vec!["01:00  RTA 01:05  Shots Fired 3  Enemies Killed 12"]
+        );
+    }
+
+    #[test]
+    fn stacked_shows_one_element_per_row() {
+        let rows = content(OverlayLayout::Stacked).rows(&data(), &TimeFormat::Minutes, 65.0);
+        assert_eq!(
+            rows,
+            vec![
+                "01:00",
+                "RTA 01:05",
+                "  3 Shots Fired",
+                " 12 Enemies Killed"
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_places_two_stats_per_row() {
+        let rows = content(OverlayLayout::Grid).rows(&data(), &TimeFormat::Minutes, 65.0);
+        assert_eq!(
+            rows,
+            vec![
+                "01:00",
+                "RTA 01:05",
+                "  3 Shots Fired     12 Enemies Killed"
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_columns_fit_the_longest_name() {
+        let mut content = content(OverlayLayout::Grid);
+        content.show_rta = false;
+        content.show_timer = false;
+        content.show_stats = [true; 8];
+
+        let rows = content.rows(&data(), &TimeFormat::Minutes, 0.0);
+        assert_eq!(rows.len(), 4);
+
+        // The second column starts at the same position in every row
+        let longest = STAT_NAMES.iter().map(|name| name.len()).max().unwrap();
+        let column = 3 + 1 + longest + 1;
+        for row in &rows {
+            assert_eq!(row.as_bytes()[column - 1], b' ');
+            assert_ne!(row.as_bytes()[column + 2], b' ');
+        }
+    }
+
+    #[test]
+    fn rating_is_only_shown_with_game_data() {
+        let content = OverlayContent {
+            show_timer: false,
+            show_rating: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            content.rows(&data(), &TimeFormat::Minutes, 0.0),
+            vec!["SILENT ASSASSIN"]
+        );
+
+        assert!(content.rows(&menus(), &TimeFormat::Minutes, 0.0).is_empty());
+    }
+
+    fn window() -> Option<Window> {
+        Some(Window {
+            left: 100,
+            top: 50,
+            right: 1100,
+            bottom: 850,
+            minimized: false,
+            focused: true,
+        })
+    }
+
+    fn position(anchor: OverlayAnchor, offset: [i32; 2]) -> OverlayPosition {
+        OverlayPosition {
+            anchor,
+            offset,
+            edit_mode: false,
+        }
+    }
+
+    #[test]
+    fn anchors_are_inside_the_window_frame() {
+        let size = [200.0, 40.0];
+        let resolve = |anchor| position(anchor, [0, 0]).resolve(&window(), size);
+
+        assert_eq!(resolve(OverlayAnchor::TopLeft), [102.0, 52.0]);
+        assert_eq!(resolve(OverlayAnchor::TopCenter), [500.0, 52.0]);
+        assert_eq!(resolve(OverlayAnchor::TopRight), [898.0, 52.0]);
+        assert_eq!(resolve(OverlayAnchor::BottomLeft), [102.0, 808.0]);
+        assert_eq!(resolve(OverlayAnchor::BottomCenter), [500.0, 808.0]);
+        assert_eq!(resolve(OverlayAnchor::BottomRight), [898.0, 808.0]);
+    }
+
+    #[test]
+    fn offsets_are_added_to_the_anchor() {
+        let size = [200.0, 40.0];
+
+        let anchored = position(OverlayAnchor::BottomRight, [-10, 5]).resolve(&window(), size);
+        assert_eq!(anchored, [888.0, 813.0]);
+
+        // Custom offsets are measured from the corner of the window
+        let custom = position(OverlayAnchor::Custom, [30, 40]).resolve(&window(), size);
+        assert_eq!(custom, [130.0, 90.0]);
+    }
+
+    #[test]
+    fn custom_position_round_trips() {
+        let mut position = position(OverlayAnchor::TopRight, [0, 0]);
+        position.set_custom(&window(), [340.4, 260.6]);
+
+        assert!(position.anchor == OverlayAnchor::Custom);
+        assert_eq!(position.offset, [240, 211]);
+        assert_eq!(position.resolve(&window(), [200.0, 40.0]), [340.0, 261.0]);
+    }
+
+    #[test]
+    fn without_a_window_the_screen_corner_is_used() {
+        let position = position(OverlayAnchor::TopLeft, [0, 0]);
+        assert_eq!(position.resolve(&None, [200.0, 40.0]), [2.0, 2.0]);
+    }
 }
@@ -0,0 +1,72 @@
+use super::colors::ColorMap;
+use super::settings::Theme;
+use super::{hotkeys::HotkeySettings, i18n::Language, layout::LayoutSettings};
+use super::{overlay::OverlaySettings, time_format::TimeFormat};
+
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+/// User settings that make up a profile. Recorded data such as best times and the mission
+/// history is shared between all profiles.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ProfileSettings {
+    pub cmap: ColorMap,
+    pub custom_cmaps: Vec<ColorMap>,
+    pub show_overlay: bool,
+    pub overlay_size: u8,
+    pub overlay: OverlaySettings,
+    pub time_format: TimeFormat,
+    pub show_rta: bool,
+    pub campaign_mode: bool,
+    pub hotkeys: HotkeySettings,
+    pub layout: LayoutSettings,
+    pub language: Language,
+    pub theme: Theme,
+}
+
+/// Named set of user settings, e.g. for streaming or practice
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Profile {
+    pub name: String,
+    pub settings: ProfileSettings,
+}
+
+impl Profile {
+    /// Write the profile to a human readable RON file that can be shared
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(path, text)
+    }
+
+    /// Read a profile written by `Profile::export`
+    pub fn import(path: &Path) -> io::Result<Profile> {
+        ron::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+
+    #[test]
+    fn profile_round_trip() {
+        let mut settings = Settings::default().profile();
+        settings.theme = Theme::Light;
+        settings.overlay_size = 9;
+        let profile = Profile {
+            name: String::from("Practice"),
+            settings,
+        };
+
+        let stored = ron::ser::to_string_pretty(&profile, Default::default()).unwrap();
+        let loaded: Profile = ron::from_str(&stored).unwrap();
+
+        assert_eq!(loaded.name, "Practice");
+        assert_eq!(loaded.settings.overlay_size, 9);
+        assert_eq!(loaded.settings.theme, Theme::Light);
+    }
+}
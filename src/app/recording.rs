@@ -0,0 +1,75 @@
+use crate::GameData;
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Records the data read from the game to a tab separated file, to diagnose memory reading
+/// issues. A line is only written when something other than the mission time changes.
+pub struct GameRecorder {
+    file: BufWriter<File>,
+    started: Instant,
+    last: Option<[String; 4]>,
+}
+
+impl GameRecorder {
+    /// Start a recording, appending to the file if it already exists
+    pub fn create(path: &Path) -> io::Result<GameRecorder> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut recorder = GameRecorder {
+            file: BufWriter::new(file),
+            started: Instant::now(),
+            last: None,
+        };
+
+        writeln!(
+            recorder.file,
+            "seconds\tmission_code\tmission_name\tmission_time\tsa_risk\tstats"
+        )?;
+        recorder.note("Recording started")?;
+        Ok(recorder)
+    }
+
+    /// Record an event, such as attaching to a game
+    pub fn note(&mut self, text: &str) -> io::Result<()> {
+        let seconds = self.started.elapsed().as_secs_f32();
+        writeln!(self.file, "{seconds:.3}\t# {text}")?;
+        self.file.flush()
+    }
+
+    /// Record the latest data read from the game
+    pub fn record(&mut self, data: &GameData) -> io::Result<()> {
+        let (sa_risk, stats) = match data.rating {
+            Some(rating) => (
+                format!("{:?}", rating.sa_risk),
+                format!("{:?}", rating.stats.to_array()),
+            ),
+            None => (String::from("-"), String::from("-")),
+        };
+        let state = [
+            data.mission_code
+                .clone()
+                .unwrap_or_else(|| String::from("-")),
+            data.mission_name.clone(),
+            sa_risk,
+            stats,
+        ];
+
+        if self.last.as_ref() == Some(&state) {
+            return Ok(());
+        }
+
+        let [code, name, sa_risk, stats] = &state;
+        let seconds = self.started.elapsed().as_secs_f32();
+        writeln!(
+            self.file,
+            "{seconds:.3}\t{code}\t{name}\t{}\t{sa_risk}\t{stats}",
+            data.mission_time
+        )?;
+        self.file.flush()?;
+
+        self.last = Some(state);
+        Ok(())
+    }
+}
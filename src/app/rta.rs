@@ -0,0 +1,157 @@
+use crate::GameData;
+
+use std::time::{Duration, Instant};
+
+// Time without progress of the in-game timer after which the game is considered to be loading
+const FREEZE_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// Real time (RTA) timer that runs alongside the in-game timer.
+/// The timer starts when a mission starts, stops when the game returns to the menus and
+/// pauses while the in-game timer is frozen to exclude loading times.
+pub struct RtaTimer {
+    mission: Option<String>,
+    running: bool,
+    elapsed: Duration,
+    last_update: Instant,
+    last_ticks: f32,
+    last_tick_change: Instant,
+}
+
+impl Default for RtaTimer {
+    fn default() -> Self {
+        RtaTimer {
+            mission: None,
+            running: false,
+            elapsed: Duration::ZERO,
+            last_update: Instant::now(),
+            last_ticks: 0.0,
+            last_tick_change: Instant::now(),
+        }
+    }
+}
+
+impl RtaTimer {
+    /// Update the timer from the latest game data
+    pub fn update(&mut self, data: &GameData) {
+        self.update_at(data, Instant::now());
+    }
+
+    fn update_at(&mut self, data: &GameData, now: Instant) {
+        // The in-game timer is only active while a mission is running
+        if data.mission_time <= 0.0 {
+            self.running = false;
+            return;
+        }
+
+        // Restart on a new mission, or when the same mission is restarted
+        let new_mission = self.mission.as_deref() != Some(data.mission_name.as_str());
+        if !self.running || new_mission || data.mission_time < self.last_ticks {
+            self.mission = Some(data.mission_name.clone());
+            self.running = true;
+            self.elapsed = Duration::ZERO;
+            self.last_update = now;
+            self.last_ticks = data.mission_time;
+            self.last_tick_change = now;
+            return;
+        }
+
+        if data.mission_time > self.last_ticks {
+            self.last_tick_change = now;
+        }
+
+        // Only count time while the in-game timer is advancing
+        if now.duration_since(self.last_tick_change) < FREEZE_THRESHOLD {
+            self.elapsed += now.duration_since(self.last_update);
+        }
+
+        self.last_update = now;
+        self.last_ticks = data.mission_time;
+    }
+
+    /// Restart the timer from zero at the next update
+    pub fn reset(&mut self) {
+        *self = RtaTimer::default();
+    }
+
+    /// Real time of the current or last mission in seconds
+    pub fn seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::backends::hm2;
+
+    fn data(name: &str, time: f32) -> GameData {
+        GameData::new(None, name.to_string(), time, None)
+    }
+
+    /// Update the timer once per 100 ms with the given in-game times
+    fn run(timer: &mut RtaTimer, start: Instant, name: &str, times: &[f32]) -> Instant {
+        let mut now = start;
+        for time in times {
+            timer.update_at(&data(name, *time), now);
+            now += Duration::from_millis(100);
+        }
+        now
+    }
+
+    #[test]
+    fn counts_real_time_while_the_game_advances() {
+        let mut timer = RtaTimer::default();
+        run(&mut timer, Instant::now(), "Miami", &[1.0, 2.0, 3.0, 4.0]);
+
+        assert!((timer.seconds() - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pauses_while_the_in_game_timer_is_frozen() {
+        let mut timer = RtaTimer::default();
+        let now = run(&mut timer, Instant::now(), "Miami", &[1.0, 2.0]);
+
+        // Loading for two seconds only counts until the freeze threshold is reached
+        let now = run(&mut timer, now, "Miami", &[2.0; 20]);
+        let paused = timer.seconds();
+        assert!((paused - 0.5).abs() < 1e-6);
+
+        // The timer resumes once the in-game timer advances again
+        run(&mut timer, now, "Miami", &[3.0, 4.0]);
+        assert!((timer.seconds() - paused - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stops_in_the_menus() {
+        let mut timer = RtaTimer::default();
+        let now = run(&mut timer, Instant::now(), "Miami", &[1.0, 2.0]);
+        run(&mut timer, now, hm2::GAME_NAME, &[0.0; 5]);
+
+        assert!((timer.seconds() - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn restarts_with_the_mission() {
+        let mut timer = RtaTimer::default();
+        let now = run(&mut timer, Instant::now(), "Miami", &[1.0, 2.0, 3.0]);
+
+        // A lower in-game time means the mission was restarted
+        let now = run(&mut timer, now, "Miami", &[0.5]);
+        assert_eq!(timer.seconds(), 0.0);
+
+        run(&mut timer, now, "Santa Fortuna", &[1.0]);
+        assert_eq!(timer.seconds(), 0.0);
+    }
+
+    #[test]
+    fn reset_starts_from_zero() {
+        let mut timer = RtaTimer::default();
+        let now = run(&mut timer, Instant::now(), "Miami", &[1.0, 2.0, 3.0]);
+
+        timer.reset();
+        assert_eq!(timer.seconds(), 0.0);
+
+        run(&mut timer, now, "Miami", &[4.0, 5.0]);
+        assert!((timer.seconds() - 0.1).abs() < 1e-6);
+    }
+}
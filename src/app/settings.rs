@@ -0,0 +1,296 @@
+use super::colors::ColorMap;
+use super::history::MissionHistory;
+use super::profiles::{Profile, ProfileSettings};
+use super::{hotkeys::HotkeySettings, i18n::Language, layout::LayoutSettings, logging::LogLevel};
+use super::{overlay::OverlaySettings, time_format::TimeFormat};
+use crate::Difficulty;
+
+use egui::Visuals;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Version of the stored settings format. Increase it whenever a change to `Settings`
+/// needs stored settings to be migrated, and add the migration to `Settings::from_stored`.
+/// Version 1 is the unversioned format of the first release, where the color map, overlay
+/// and theme were fields of `App`.
+pub const SETTINGS_VERSION: u32 = 2;
+
+/// Color theme of the interface
+#[derive(Copy, Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub enum Theme {
+    Light,
+    #[default]
+    Dark,
+}
+
+impl Theme {
+    pub fn visuals(&self) -> Visuals {
+        match self {
+            Theme::Light => Visuals::light(),
+            Theme::Dark => Visuals::dark(),
+        }
+    }
+}
+
+/// Persistent user settings and recorded data, kept separate from the runtime state of the
+/// application. Missing fields are filled with their defaults, so adding a field does not
+/// need a new version.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+
+    // Interface
+    pub cmap: ColorMap,
+    pub custom_cmaps: Vec<ColorMap>,
+    pub show_overlay: bool,
+    pub overlay_size: u8,
+    pub overlay: OverlaySettings,
+    pub time_format: TimeFormat,
+    pub show_rta: bool,
+    pub campaign_mode: bool,
+    pub hotkeys: HotkeySettings,
+    pub layout: LayoutSettings,
+    pub language: Language,
+    pub theme: Theme,
+    pub log_level: LogLevel,
+
+    // Profiles
+    pub profiles: Vec<Profile>,
+    pub active_profile: String,
+    pub profile_path: String,
+
+    // Recorded data
+    /// Difficulty of new records, selected manually as it is not read from the game
+    pub difficulty: Difficulty,
+    pub records: HashMap<Difficulty, Records>,
+    pub lss_path: String,
+    pub history: MissionHistory,
+    pub history_path: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            cmap: ColorMap::default(),
+            custom_cmaps: Vec::new(),
+            show_overlay: false,
+            overlay_size: 5,
+            overlay: OverlaySettings::default(),
+            time_format: TimeFormat::Minutes,
+            show_rta: false,
+            campaign_mode: false,
+            hotkeys: HotkeySettings::default(),
+            layout: LayoutSettings::default(),
+            language: Language::default(),
+            theme: Theme::default(),
+            log_level: LogLevel::default(),
+            profiles: Vec::new(),
+            active_profile: String::from("Default"),
+            profile_path: String::from("profile.ron"),
+            difficulty: Difficulty::default(),
+            records: HashMap::new(),
+            lss_path: String::from("splits.lss"),
+            history: MissionHistory::default(),
+            history_path: String::from("history"),
+        }
+    }
+}
+
+impl Settings {
+    /// Load the settings of the previous session, or the defaults if there are none
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Settings {
+        let stored = match storage.and_then(|storage| storage.get_string(eframe::APP_KEY)) {
+            Some(stored) => stored,
+            None => return Settings::default(),
+        };
+
+        Settings::from_stored(&stored).unwrap_or_else(|err| {
+            warn!(%err, "Failed to load the stored settings, using the defaults");
+            Settings::default()
+        })
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    /// Parse stored settings of any version, migrating them to the current version.
+    /// Settings stored by a newer version are read as far as the current format allows.
+    pub fn from_stored(stored: &str) -> Result<Settings, ron::error::SpannedError> {
+        let version = ron::from_str::<StoredVersion>(stored)?.version;
+        let mut settings = match version {
+            1 => ron::from_str::<SettingsV1>(stored)?.migrate(),
+            _ => ron::from_str::<Settings>(stored)?,
+        };
+
+        if version != SETTINGS_VERSION {
+            info!(
+                from = version,
+                to = SETTINGS_VERSION,
+                "Migrated stored settings"
+            );
+        }
+
+        settings.version = SETTINGS_VERSION;
+        Ok(settings)
+    }
+
+    /// Records of the current difficulty
+    pub fn records(&mut self) -> &mut Records {
+        self.records.entry(self.difficulty).or_default()
+    }
+
+    /// The settings that are saved as part of a profile
+    pub fn profile(&self) -> ProfileSettings {
+        ProfileSettings {
+            cmap: self.cmap.clone(),
+            custom_cmaps: self.custom_cmaps.clone(),
+            show_overlay: self.show_overlay,
+            overlay_size: self.overlay_size,
+            overlay: self.overlay.clone(),
+            time_format: self.time_format,
+            show_rta: self.show_rta,
+            campaign_mode: self.campaign_mode,
+            hotkeys: self.hotkeys.clone(),
+            layout: self.layout.clone(),
+            language: self.language,
+            theme: self.theme,
+        }
+    }
+
+    /// Replace the current settings with those of a profile
+    pub fn apply_profile(&mut self, profile: ProfileSettings) {
+        self.cmap = profile.cmap;
+        self.custom_cmaps = profile.custom_cmaps;
+        self.show_overlay = profile.show_overlay;
+        self.overlay_size = profile.overlay_size;
+        self.overlay = profile.overlay;
+        self.time_format = profile.time_format;
+        self.show_rta = profile.show_rta;
+        self.campaign_mode = profile.campaign_mode;
+        self.hotkeys = profile.hotkeys;
+        self.layout = profile.layout;
+        self.language = profile.language;
+        self.theme = profile.theme;
+    }
+}
+
+/// Best times and split targets of the missions played on a single difficulty
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Records {
+    /// Best times by mission code
+    pub best_times: HashMap<String, f32>,
+    /// Split targets by mission code
+    pub split_targets: HashMap<String, f32>,
+}
+
+/// Version of the stored settings, where those without a version are version 1
+#[derive(Deserialize)]
+struct StoredVersion {
+    #[serde(default = "StoredVersion::unversioned")]
+    version: u32,
+}
+
+impl StoredVersion {
+    fn unversioned() -> u32 {
+        1
+    }
+}
+
+/// Version 1: the persistent fields of `App`, with the theme stored as the full `Visuals`
+#[derive(Deserialize)]
+#[serde(default)]
+struct SettingsV1 {
+    cmap: ColorMap,
+    show_overlay: bool,
+    overlay_size: u8,
+    theme: Visuals,
+}
+
+impl Default for SettingsV1 {
+    fn default() -> Self {
+        let defaults = Settings::default();
+        SettingsV1 {
+            cmap: defaults.cmap,
+            show_overlay: defaults.show_overlay,
+            overlay_size: defaults.overlay_size,
+            theme: Visuals::dark(),
+        }
+    }
+}
+
+impl SettingsV1 {
+    fn migrate(self) -> Settings {
+        // Only the light or dark mode of the stored `Visuals` is kept
+        let theme = if self.theme.dark_mode {
+            Theme::Dark
+        } else {
+            Theme::Light
+        };
+
+        Settings {
+            cmap: self.cmap,
+            show_overlay: self.show_overlay,
+            overlay_size: self.overlay_size,
+            theme,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_original_settings() {
+        // Settings as stored by the first release, before rating risk colors existed
+        let stored = r#"(cmap:(label:"Blue / Red",sa_true:((0,90,180,255)),sa_false:((220,50,32,255))),show_overlay:true,overlay_size:7,theme:(dark_mode:false))"#;
+        let settings = Settings::from_stored(stored).unwrap();
+
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.cmap.get_label(), "Blue / Red");
+        assert_eq!(
+            settings.cmap.get_sa_risk(),
+            ColorMap::default().get_sa_risk()
+        );
+        assert!(settings.show_overlay);
+        assert_eq!(settings.overlay_size, 7);
+        assert_eq!(settings.theme, Theme::Light);
+        assert!(settings.time_format == TimeFormat::Minutes);
+    }
+
+    #[test]
+    fn current_settings_round_trip() {
+        let mut settings = Settings {
+            overlay_size: 9,
+            theme: Theme::Light,
+            ..Default::default()
+        };
+        settings.difficulty = Difficulty::Professional;
+        settings
+            .records()
+            .best_times
+            .insert(String::from("C5-3\\"), 1234.0);
+
+        let stored = ron::to_string(&settings).unwrap();
+        let loaded = Settings::from_stored(&stored).unwrap();
+
+        assert_eq!(loaded.version, SETTINGS_VERSION);
+        assert_eq!(loaded.overlay_size, 9);
+        assert_eq!(loaded.theme, Theme::Light);
+        assert_eq!(loaded.difficulty, Difficulty::Professional);
+        let records = &loaded.records[&Difficulty::Professional];
+        assert_eq!(records.best_times.get("C5-3\\"), Some(&1234.0));
+        assert!(!loaded.records.contains_key(&Difficulty::Normal));
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        assert!(Settings::from_stored("not settings").is_err());
+    }
+}
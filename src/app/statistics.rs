@@ -0,0 +1,139 @@
+use super::history::Attempt;
+use crate::Difficulty;
+
+// Length of a day in seconds, used to group attempts into sessions
+const SESSION_LENGTH: u64 = 86400;
+
+/// Aggregated results of all stored attempts of a single mission
+/// The times only include completed attempts, as abandoned attempts end at an arbitrary time
+pub struct MissionSummary {
+    pub attempts: usize,
+    pub sa_attempts: usize,
+    pub rated_attempts: usize,
+    pub best_time: Option<f32>,
+    pub median_time: Option<f32>,
+    pub sa_breaker: Option<usize>,
+    pub session_best: Vec<f32>,
+}
+
+impl MissionSummary {
+    /// Summarize the attempts of the given mission code on a difficulty
+    pub fn new(attempts: &[Attempt], code: &str, difficulty: Difficulty) -> Self {
+        let attempts: Vec<&Attempt> = attempts
+            .iter()
+            .filter(|attempt| attempt.mission_code == code && attempt.difficulty == difficulty)
+            .collect();
+
+        let rated: Vec<bool> = attempts.iter().filter_map(|a| a.sa_rating).collect();
+
+        let completed: Vec<&Attempt> = attempts.iter().copied().filter(|a| a.completed).collect();
+
+        let mut times: Vec<f32> = completed.iter().map(|attempt| attempt.time).collect();
+        times.sort_by(f32::total_cmp);
+
+        // Count how often each stat was the one to lose the SA rating
+        let mut breakers = [0; 8];
+        for index in attempts.iter().filter_map(|a| a.sa_breaker) {
+            if let Some(count) = breakers.get_mut(index) {
+                *count += 1;
+            }
+        }
+        let sa_breaker = (0..breakers.len())
+            .filter(|i| breakers[*i] > 0)
+            .max_by_key(|i| breakers[*i]);
+
+        // Best time of each day with attempts, oldest first
+        let mut session_best: Vec<(u64, f32)> = Vec::new();
+        for attempt in &completed {
+            let session = attempt.date / SESSION_LENGTH;
+            match session_best.iter_mut().find(|(day, _)| *day == session) {
+                Some((_, best)) => *best = best.min(attempt.time),
+                None => session_best.push((session, attempt.time)),
+            }
+        }
+        session_best.sort_by_key(|(day, _)| *day);
+
+        MissionSummary {
+            attempts: attempts.len(),
+            sa_attempts: rated.iter().filter(|sa| **sa).count(),
+            rated_attempts: rated.len(),
+            best_time: times.first().copied(),
+            median_time: median(&times),
+            sa_breaker,
+            session_best: session_best.into_iter().map(|(_, best)| best).collect(),
+        }
+    }
+
+    /// Share of the rated attempts that got the SA rating
+    pub fn sa_rate(&self) -> Option<f32> {
+        (self.rated_attempts > 0).then(|| self.sa_attempts as f32 / self.rated_attempts as f32)
+    }
+}
+
+/// Codes of all missions with stored attempts on a difficulty, in the order they were
+/// first played
+pub fn played_missions(attempts: &[Attempt], difficulty: Difficulty) -> Vec<String> {
+    let mut codes: Vec<String> = Vec::new();
+    for attempt in attempts.iter().filter(|a| a.difficulty == difficulty) {
+        if !codes.contains(&attempt.mission_code) {
+            codes.push(attempt.mission_code.clone());
+        }
+    }
+    codes
+}
+
+/// Median of sorted values
+fn median(sorted: &[f32]) -> Option<f32> {
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_fixtures::attempt;
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[3.0]), Some(3.0));
+        assert_eq!(median(&[1.0, 2.0, 9.0]), Some(2.0));
+        assert_eq!(median(&[1.0, 2.0, 4.0, 9.0]), Some(3.0));
+    }
+
+    #[test]
+    fn times_only_include_completed_attempts() {
+        let day = SESSION_LENGTH;
+        let attempts = [
+            attempt("C1-1\\", 0, 900.0, true),
+            // Abandoned early, would otherwise be the best time of the first session
+            attempt("C1-1\\", 10, 100.0, false),
+            attempt("C1-1\\", day, 700.0, true),
+            attempt("C1-1\\", day + 10, 800.0, true),
+            attempt("C1-1\\", 2 * day, 50.0, false),
+            attempt("C2-2\\", 0, 10.0, true),
+        ];
+
+        let summary = MissionSummary::new(&attempts, "C1-1\\", Difficulty::Normal);
+        assert_eq!(summary.attempts, 5);
+        assert_eq!(summary.rated_attempts, 5);
+        assert_eq!(summary.sa_attempts, 3);
+        assert_eq!(summary.best_time, Some(700.0));
+        assert_eq!(summary.median_time, Some(800.0));
+        assert_eq!(summary.session_best, vec![900.0, 700.0]);
+    }
+
+    #[test]
+    fn other_difficulties_are_ignored() {
+        let attempts = [attempt("C1-1\\", 0, 900.0, true)];
+
+        let summary = MissionSummary::new(&attempts, "C1-1\\", Difficulty::Expert);
+        assert_eq!(summary.attempts, 0);
+        assert_eq!(summary.best_time, None);
+        assert_eq!(summary.sa_rate(), None);
+    }
+}
@@ -1,33 +1,180 @@
-use super::backends::{hm2::Hm2, hmc::HmC};
+use super::backends::{hm2, hm2::Hm2, hmc, hmc::HmC};
 use crate::{Backend, Window};
 
-use std::ptr::null;
-use sysinfo::System;
+use sysinfo::{Pid, Process, ProcessRefreshKind, System, UpdateKind};
+use tracing::{info, trace};
 use windows_sys::Win32::{
-    Foundation::{HWND, RECT},
-    UI::WindowsAndMessaging::{FindWindowW, GetWindowRect},
+    Foundation::{BOOL, HWND, LPARAM, RECT},
+    UI::WindowsAndMessaging::{
+        EnumWindows, GetForegroundWindow, GetWindowRect, GetWindowThreadProcessId, IsIconic,
+        IsWindowVisible,
+    },
 };
 
-/// Get the process ID of available games
-pub fn get_game(sys: &mut System) -> Option<Box<dyn Backend>> {
-    // Refresh all runnning processes and match name with supported games
-    sys.refresh_processes();
+/// Games supported by the tracker
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Game {
+    Hm2,
+    HmC,
+}
 
-    // Check if Hitman 2 SA is running:
-    if let Some(process) = sys.processes_by_exact_name("hitman2.exe").next() {
-        return Some(Box::new(Hm2::new(process.pid().as_u32())));
+impl Game {
+    pub fn all_games() -> [Game; 2] {
+        [Game::Hm2, Game::HmC]
     }
 
-    // Check if Hitman Contracts is running:
-    if let Some(process) = sys.processes_by_exact_name("HitmanContracts.exe").next() {
-        return Some(Box::new(HmC::new(process.pid().as_u32())));
+    /// Short name used to select the game on the command line
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Game::Hm2 => "hm2",
+            Game::HmC => "hmc",
+        }
     }
 
-    None
+    /// Full title of the game
+    pub fn game_name(&self) -> &'static str {
+        match self {
+            Game::Hm2 => hm2::GAME_NAME,
+            Game::HmC => hmc::GAME_NAME,
+        }
+    }
+
+    /// Executable name of the game process
+    pub fn process_name(&self) -> &'static str {
+        match self {
+            Game::Hm2 => "hitman2.exe",
+            Game::HmC => "HitmanContracts.exe",
+        }
+    }
+
+    /// Check if a process is an instance of the game. Processes hosted by Wine may have their
+    /// name cut to 15 characters, while the full executable name is kept in the command line.
+    fn matches(&self, process: &Process) -> bool {
+        let expected = self.process_name().to_lowercase();
+        let name = process.name().to_lowercase();
+        let command = process
+            .cmd()
+            .first()
+            .and_then(|cmd| cmd.rsplit(['/', '\\']).next())
+            .map(|cmd| cmd.to_lowercase());
+
+        name == expected
+            || (name.len() >= 15 && expected.starts_with(&name))
+            || command.is_some_and(|command| command == expected)
+    }
+}
+
+/// A running process of one of the supported games
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameProcess {
+    pub game: Game,
+    pub pid: u32,
+    /// Start of the process in seconds since the Unix epoch
+    pub start_time: u64,
+}
+
+impl GameProcess {
+    /// Create the backend for reading the data of the process
+    pub fn attach(&self) -> Box<dyn Backend> {
+        match self.game {
+            Game::Hm2 => Box::new(Hm2::new(self.pid)),
+            Game::HmC => Box::new(HmC::new(self.pid)),
+        }
+    }
+}
+
+/// Choice of game process to attach to, made on the command line or in the process picker.
+/// Only matching processes are attached to, and an unset pin matches any process.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct ProcessPin {
+    pub game: Option<Game>,
+    pub pid: Option<u32>,
+}
+
+impl ProcessPin {
+    pub fn matches(&self, process: &GameProcess) -> bool {
+        self.game.map_or(true, |game| game == process.game)
+            && self.pid.map_or(true, |pid| pid == process.pid)
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.game.is_some() || self.pid.is_some()
+    }
+
+    /// Move a pin of a closed process to its game, so that the next instance of the same
+    /// game is attached to automatically
+    pub fn release(&mut self, process: &GameProcess) {
+        if self.pid == Some(process.pid) {
+            *self = ProcessPin {
+                game: Some(process.game),
+                pid: None,
+            };
+        }
+    }
 }
 
-/// Get the on screen position of the active game window
-pub fn get_process_window(name: &str) -> Option<Window> {
+/// Get all running processes of the supported games, oldest first
+pub fn find_games(sys: &mut System) -> Vec<GameProcess> {
+    // Refresh all runnning processes, including the command line to find Wine hosted games
+    sys.refresh_processes_specifics(ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet));
+
+    let mut processes: Vec<GameProcess> = sys
+        .processes()
+        .values()
+        .filter_map(|process| {
+            let game = Game::all_games()
+                .into_iter()
+                .find(|game| game.matches(process))?;
+
+            Some(GameProcess {
+                game,
+                pid: process.pid().as_u32(),
+                start_time: process.start_time(),
+            })
+        })
+        .collect();
+
+    processes.sort_by_key(|process| (process.start_time, process.pid));
+    trace!(?processes, "Searched for game processes");
+    processes
+}
+
+/// Check if a game process is still running. The start time is compared as well, since the
+/// process ID can be reused by a new process once the game has closed.
+pub fn process_running(sys: &mut System, process: &GameProcess) -> bool {
+    let pid = Pid::from_u32(process.pid);
+    let running = sys.refresh_process_specifics(pid, ProcessRefreshKind::new())
+        && sys
+            .process(pid)
+            .is_some_and(|running| running.start_time() == process.start_time);
+
+    if !running {
+        info!(pid = process.pid, "Game process exited");
+    }
+    running
+}
+
+/// Window search passed to `EnumWindows`, holding the process and the window found
+struct WindowSearch {
+    pid: u32,
+    window: HWND,
+}
+
+/// Callback of `EnumWindows` that stops at the first visible window of the searched process
+unsafe extern "system" fn find_process_window(window: HWND, search: LPARAM) -> BOOL {
+    let search = &mut *(search as *mut WindowSearch);
+
+    let mut pid = 0;
+    GetWindowThreadProcessId(window, &mut pid);
+    if pid == search.pid && IsWindowVisible(window) != 0 {
+        search.window = window;
+        return 0;
+    }
+    1
+}
+
+/// Get the on screen position and state of the main window of a game process
+pub fn get_process_window(pid: u32) -> Option<Window> {
     let mut rect = RECT {
         left: 0,
         top: 0,
@@ -35,13 +182,15 @@ pub fn get_process_window(name: &str) -> Option<Window> {
         bottom: 0,
     };
 
-    // Get the window ID of the game
-    let id: HWND = {
-        // The Win32 API requires a null-terminated wide string
-        let mut name = name.encode_utf16().collect::<Vec<u16>>();
-        name.push(0);
-        unsafe { FindWindowW(null(), name.as_ptr()) }
+    // Get the window ID of the game from the windows of its process
+    let mut search = WindowSearch { pid, window: 0 };
+    unsafe {
+        EnumWindows(
+            Some(find_process_window),
+            &mut search as *mut WindowSearch as LPARAM,
+        )
     };
+    let id = search.window;
 
     if id != 0 && unsafe { GetWindowRect(id, &mut rect) } != 0 {
         return Some(Window {
@@ -49,8 +198,89 @@ pub fn get_process_window(name: &str) -> Option<Window> {
             top: rect.top,
             right: rect.right,
             bottom: rect.bottom,
+            minimized: unsafe { IsIconic(id) } != 0,
+            focused: unsafe { GetForegroundWindow() } == id,
         });
     }
 
+    trace!(pid, "Game window not found");
     None
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(game: Game, pid: u32) -> GameProcess {
+        GameProcess {
+            game,
+            pid,
+            start_time: 0,
+        }
+    }
+
+    #[test]
+    fn pins_match_their_game_and_process() {
+        let hm2 = process(Game::Hm2, 100);
+        let hmc = process(Game::HmC, 200);
+
+        let unset = ProcessPin::default();
+        assert!(!unset.is_set());
+        assert!(unset.matches(&hm2) && unset.matches(&hmc));
+
+        let game = ProcessPin {
+            game: Some(Game::HmC),
+            pid: None,
+        };
+        assert!(!game.matches(&hm2) && game.matches(&hmc));
+
+        let pid = ProcessPin {
+            game: None,
+            pid: Some(100),
+        };
+        assert!(pid.matches(&hm2) && !pid.matches(&hmc));
+
+        // Both have to match, e.g. after a process ID was reused by another game
+        let both = ProcessPin {
+            game: Some(Game::HmC),
+            pid: Some(100),
+        };
+        assert!(!both.matches(&hm2) && !both.matches(&hmc));
+    }
+
+    #[test]
+    fn released_pins_follow_the_game() {
+        let closed = process(Game::Hm2, 100);
+        let mut pin = ProcessPin {
+            game: Some(Game::Hm2),
+            pid: Some(100),
+        };
+
+        pin.release(&closed);
+        assert_eq!(
+            pin,
+            ProcessPin {
+                game: Some(Game::Hm2),
+                pid: None,
+            }
+        );
+        assert!(pin.matches(&process(Game::Hm2, 300)));
+        assert!(!pin.matches(&process(Game::HmC, 300)));
+    }
+
+    #[test]
+    fn pins_of_other_processes_are_kept() {
+        let pinned = ProcessPin {
+            game: None,
+            pid: Some(200),
+        };
+
+        let mut pin = pinned.clone();
+        pin.release(&process(Game::Hm2, 100));
+        assert_eq!(pin, pinned);
+
+        let mut pin = ProcessPin::default();
+        pin.release(&process(Game::Hm2, 100));
+        assert_eq!(pin, ProcessPin::default());
+    }
+}
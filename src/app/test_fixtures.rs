@@ -0,0 +1,30 @@
+use super::backends::hm2;
+use super::history::Attempt;
+use crate::{Difficulty, GameData, MissionStats};
+
+/// Game data of a Hitman 2 mission at the given in-game time
+pub fn game_data(code: &str, time: f32) -> GameData {
+    let name = hm2::missions().get(code).unwrap().name;
+    GameData::new(Some(code.to_string()), name.to_string(), time, None)
+}
+
+/// Game data while the game is in the menus
+pub fn menus() -> GameData {
+    GameData::new(None, hm2::GAME_NAME.to_string(), 0.0, None)
+}
+
+/// Attempt at a Hitman 2 mission on normal difficulty, rated SA if it was completed
+pub fn attempt(code: &str, date: u64, time: f32, completed: bool) -> Attempt {
+    Attempt {
+        game: hm2::GAME_NAME.to_string(),
+        mission_code: code.to_string(),
+        mission_name: hm2::missions().get(code).unwrap().name.to_string(),
+        date,
+        time,
+        stats: MissionStats::default(),
+        sa_rating: Some(completed),
+        sa_breaker: None,
+        difficulty: Difficulty::Normal,
+        completed,
+    }
+}
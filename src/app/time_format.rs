@@ -0,0 +1,108 @@
+/// The in-game timers of both games run at 60 ticks per second
+pub const TICKS_PER_SECOND: f32 = 60.0;
+
+/// Available formats for displaying the mission timer
+#[derive(PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
+pub enum TimeFormat {
+    Minutes,
+    Centiseconds,
+    Hours,
+    Ticks,
+}
+
+impl TimeFormat {
+    pub fn all_formats() -> [Self; 4] {
+        [
+            TimeFormat::Minutes,
+            TimeFormat::Centiseconds,
+            TimeFormat::Hours,
+            TimeFormat::Ticks,
+        ]
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            TimeFormat::Minutes => "MM:SS",
+            TimeFormat::Centiseconds => "MM:SS.cc",
+            TimeFormat::Hours => "H:MM:SS",
+            TimeFormat::Ticks => "Ticks",
+        }
+    }
+
+    /// Format a time given in game ticks
+    pub fn format_ticks(&self, ticks: f32) -> String {
+        match self {
+            TimeFormat::Ticks => format!("{}", ticks.max(0.0) as u64),
+            _ => self.format_seconds(ticks / TICKS_PER_SECOND),
+        }
+    }
+
+    /// Format a time given in seconds
+    pub fn format_seconds(&self, seconds: f32) -> String {
+        // Work in whole centiseconds to avoid rounding up to the next second when displayed
+        let centis = (seconds.max(0.0) * 100.0) as u64;
+        let total_seconds = centis / 100;
+
+        match self {
+            TimeFormat::Minutes => format!("{:0>2}:{:0>2}", total_seconds / 60, total_seconds % 60),
+            TimeFormat::Centiseconds => format!(
+                "{:0>2}:{:0>2}.{:0>2}",
+                total_seconds / 60,
+                total_seconds % 60,
+                centis % 100
+            ),
+            TimeFormat::Hours => format!(
+                "{}:{:0>2}:{:0>2}",
+                total_seconds / 3600,
+                (total_seconds / 60) % 60,
+                total_seconds % 60
+            ),
+            TimeFormat::Ticks => format!("{}", (seconds.max(0.0) * TICKS_PER_SECOND) as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_are_truncated_instead_of_rounded() {
+        assert_eq!(TimeFormat::Minutes.format_seconds(59.999), "00:59");
+        assert_eq!(TimeFormat::Centiseconds.format_seconds(59.999), "00:59.99");
+        assert_eq!(TimeFormat::Hours.format_seconds(59.999), "0:00:59");
+        assert_eq!(TimeFormat::Ticks.format_seconds(0.99), "59");
+    }
+
+    #[test]
+    fn hours_roll_over() {
+        assert_eq!(TimeFormat::Hours.format_seconds(3599.5), "0:59:59");
+        assert_eq!(TimeFormat::Hours.format_seconds(3600.0), "1:00:00");
+        assert_eq!(TimeFormat::Hours.format_seconds(3661.0), "1:01:01");
+
+        // The minute formats keep counting minutes past the hour
+        assert_eq!(TimeFormat::Minutes.format_seconds(3661.0), "61:01");
+        assert_eq!(TimeFormat::Centiseconds.format_seconds(3600.0), "60:00.00");
+    }
+
+    #[test]
+    fn zero_and_negative_times_show_zero() {
+        for format in TimeFormat::all_formats() {
+            let zero = format.format_seconds(0.0);
+            assert_eq!(format.format_seconds(-5.0), zero);
+            assert_eq!(format.format_ticks(-300.0), zero);
+        }
+
+        assert_eq!(TimeFormat::Minutes.format_seconds(0.0), "00:00");
+        assert_eq!(TimeFormat::Centiseconds.format_seconds(0.0), "00:00.00");
+        assert_eq!(TimeFormat::Hours.format_seconds(0.0), "0:00:00");
+        assert_eq!(TimeFormat::Ticks.format_seconds(0.0), "0");
+    }
+
+    #[test]
+    fn ticks_are_converted_to_seconds() {
+        assert_eq!(TimeFormat::Minutes.format_ticks(3600.0), "01:00");
+        assert_eq!(TimeFormat::Centiseconds.format_ticks(90.0), "00:01.50");
+        assert_eq!(TimeFormat::Ticks.format_ticks(90.0), "90");
+    }
+}
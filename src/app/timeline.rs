@@ -0,0 +1,139 @@
+use crate::{GameData, MissionStats};
+
+/// Increase of a single stat during the mission
+pub struct TimelineEvent {
+    pub time: f32,
+    pub stat: usize,
+    pub increase: u32,
+}
+
+/// Record of the stat changes during the current or last mission, sampled every update.
+/// The timeline is kept after the mission ends so the run can be reviewed in the menus.
+#[derive(Default)]
+pub struct MissionTimeline {
+    mission: Option<String>,
+    events: Vec<TimelineEvent>,
+    sa_lost: Option<f32>,
+    last_stats: MissionStats,
+    last_sa: Option<bool>,
+    end_time: f32,
+}
+
+impl MissionTimeline {
+    /// Sample the latest game data
+    pub fn update(&mut self, data: &GameData) {
+        if data.mission_time <= 0.0 {
+            return;
+        }
+
+        // Start over on a new mission, or when the same mission is restarted
+        let new_mission = self.mission.as_deref() != Some(data.mission_name.as_str());
+        if new_mission || data.mission_time < self.end_time {
+            *self = MissionTimeline {
+                mission: Some(data.mission_name.clone()),
+                ..Default::default()
+            };
+        }
+
+        let stats = data.rating.map(|r| r.stats).unwrap_or_default();
+        let sa_rating = data.rating.map(|r| r.sa_rating);
+
+        for (stat, (new, old)) in stats
+            .to_array()
+            .into_iter()
+            .zip(self.last_stats.to_array())
+            .enumerate()
+        {
+            if new > old {
+                self.events.push(TimelineEvent {
+                    time: data.mission_time,
+                    stat,
+                    increase: new - old,
+                });
+            }
+        }
+
+        // Also mark a mission that is already lost at the first sample, e.g. when the
+        // tracker is attached mid-mission
+        if sa_rating == Some(false) && self.last_sa != Some(false) {
+            self.sa_lost = Some(data.mission_time);
+        }
+
+        self.last_stats = stats;
+        self.last_sa = sa_rating;
+        self.end_time = data.mission_time;
+    }
+
+    pub fn events(&self) -> &[TimelineEvent] {
+        &self.events
+    }
+
+    /// Mission time in ticks at which the SA rating was lost
+    pub fn sa_lost(&self) -> Option<f32> {
+        self.sa_lost
+    }
+
+    /// Mission time in ticks of the latest sample
+    pub fn end_time(&self) -> f32 {
+        self.end_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rating, SaRisk};
+
+    fn data(time: f32, enemies_killed: u32, sa_risk: SaRisk) -> GameData {
+        let stats = MissionStats {
+            enemies_killed,
+            ..Default::default()
+        };
+        let rating = Rating::new(stats, sa_risk);
+        GameData::new(None, String::from("Miami"), time, Some(rating))
+    }
+
+    #[test]
+    fn records_stat_increases() {
+        let mut timeline = MissionTimeline::default();
+        timeline.update(&data(60.0, 0, SaRisk::Safe));
+        timeline.update(&data(120.0, 2, SaRisk::Lost));
+
+        let events = timeline.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].time, 120.0);
+        assert_eq!(events[0].stat, 4);
+        assert_eq!(events[0].increase, 2);
+        assert_eq!(timeline.end_time(), 120.0);
+    }
+
+    #[test]
+    fn marks_when_sa_is_lost() {
+        let mut timeline = MissionTimeline::default();
+        timeline.update(&data(60.0, 0, SaRisk::Safe));
+        assert_eq!(timeline.sa_lost(), None);
+
+        timeline.update(&data(120.0, 1, SaRisk::Lost));
+        timeline.update(&data(180.0, 1, SaRisk::Lost));
+        assert_eq!(timeline.sa_lost(), Some(120.0));
+    }
+
+    #[test]
+    fn marks_missions_that_start_lost() {
+        let mut timeline = MissionTimeline::default();
+        timeline.update(&data(600.0, 1, SaRisk::Lost));
+        timeline.update(&data(660.0, 1, SaRisk::Lost));
+
+        assert_eq!(timeline.sa_lost(), Some(600.0));
+    }
+
+    #[test]
+    fn restart_clears_the_timeline() {
+        let mut timeline = MissionTimeline::default();
+        timeline.update(&data(600.0, 1, SaRisk::Lost));
+        timeline.update(&data(30.0, 0, SaRisk::Safe));
+
+        assert!(timeline.events().is_empty());
+        assert_eq!(timeline.sa_lost(), None);
+    }
+}
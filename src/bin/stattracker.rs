@@ -2,31 +2,63 @@
 #![allow(non_snake_case)] // Allow executable name to be in Pascal Case
 
 use eframe::egui::{IconData, ViewportBuilder};
-use stattracker::app::main::App;
+use stattracker::app::{
+    launch::{LaunchOptions, APP_NAME, USAGE},
+    main::App,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    MessageBoxW, MB_ICONERROR, MB_ICONINFORMATION, MB_OK, MESSAGEBOX_STYLE,
+};
 
-// Define Window size
+// Define Window size, the window can be resized down to the minimum size for smaller layouts
 const WIDTH: f32 = 270.0;
 const HEIGHT: f32 = 330.0;
+const MIN_WIDTH: f32 = 150.0;
+const MIN_HEIGHT: f32 = 40.0;
 
 /// Run the application
 fn main() -> eframe::Result<(), eframe::Error> {
+    // Read the options given on the command line, e.g. by a game launcher
+    let launch = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(launch) => launch,
+        Err(err) => {
+            show_message(&format!("{err}\n\n{USAGE}"), MB_ICONERROR);
+            std::process::exit(2);
+        }
+    };
+
+    if launch.help {
+        show_message(USAGE, MB_ICONINFORMATION);
+        return Ok(());
+    }
+
     // Set up the window for the application
     eframe::run_native(
-        "Hitman StatTracker",
+        APP_NAME,
         eframe::NativeOptions {
             viewport: ViewportBuilder::default()
                 .with_inner_size([WIDTH, HEIGHT])
-                .with_min_inner_size([WIDTH, HEIGHT])
-                .with_resizable(false)
+                .with_min_inner_size([MIN_WIDTH, MIN_HEIGHT])
+                .with_resizable(true)
                 .with_icon(load_icon()),
             run_and_return: false,
+            // Store the settings in the given file instead of the default location
+            persistence_path: launch.config.clone(),
             ..Default::default()
         },
         // Launch the GUI
-        Box::new(|cc| Ok(Box::new(App::new(cc)))),
+        Box::new(|cc| Ok(Box::new(App::new(cc, launch)))),
     )
 }
 
+/// Show a message in a dialog, as the app has no console to print to
+fn show_message(text: &str, icon: MESSAGEBOX_STYLE) {
+    // The Win32 API requires null-terminated wide strings
+    let wide = |text: &str| text.encode_utf16().chain([0]).collect::<Vec<u16>>();
+    let (text, caption) = (wide(text), wide(APP_NAME));
+    unsafe { MessageBoxW(0, text.as_ptr(), caption.as_ptr(), MB_OK | icon) };
+}
+
 /// load the icon for the application
 pub fn load_icon() -> IconData {
     let (icon_rgba, icon_width, icon_height) = {
@@ -44,4 +76,4 @@ pub fn load_icon() -> IconData {
         width: icon_width,
         height: icon_height,
     }
-}
\ No newline at end of file
+}
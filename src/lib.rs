@@ -3,18 +3,39 @@ pub trait Backend {
     fn update(&mut self) -> Option<GameData>;
 
     fn game_window(&self) -> Option<Window>;
+
+    /// Catalog of all missions of the game in story order
+    fn missions(&self) -> &'static app::backends::missions::MissionTable;
+
+    /// Full title of the game
+    fn game_name(&self) -> &'static str;
+
+    /// Difficulty levels offered by the game
+    fn difficulties(&self) -> &'static [Difficulty];
+
+    /// ID of the game process that is read
+    fn pid(&self) -> u32;
 }
 
 /// Structs for passing data retrieved from the game to the GUI
+/// The mission time is given in game ticks (60 per second)
+/// The mission code is only set while a mission is loaded, not in the menus
 pub struct GameData {
+    pub mission_code: Option<String>,
     pub mission_name: String,
-    pub mission_time: u32,
+    pub mission_time: f32,
     pub rating: Option<Rating>,
 }
 
 impl GameData {
-    pub fn new(mission_name: String, mission_time: u32, rating: Option<Rating>) -> Self {
+    pub fn new(
+        mission_code: Option<String>,
+        mission_name: String,
+        mission_time: f32,
+        rating: Option<Rating>,
+    ) -> Self {
         GameData {
+            mission_code,
             mission_name,
             mission_time,
             rating,
@@ -22,18 +43,65 @@ impl GameData {
     }
 }
 
+/// Difficulty level selected in the game. It is not read from the game, so the user selects
+/// it manually. Records and stored attempts are kept separately for each difficulty.
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Debug, Default, serde::Deserialize, serde::Serialize,
+)]
+pub enum Difficulty {
+    Rookie,
+    #[default]
+    Normal,
+    Expert,
+    Professional,
+}
+
+impl Difficulty {
+    pub fn all_difficulties() -> [Difficulty; 4] {
+        [
+            Difficulty::Rookie,
+            Difficulty::Normal,
+            Difficulty::Expert,
+            Difficulty::Professional,
+        ]
+    }
+
+    pub fn get_label(&self) -> &str {
+        match self {
+            Difficulty::Rookie => "Rookie",
+            Difficulty::Normal => "Normal",
+            Difficulty::Expert => "Expert",
+            Difficulty::Professional => "Professional",
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Rating {
     pub stats: MissionStats,
     pub sa_rating: bool,
+    pub sa_risk: SaRisk,
 }
 
 impl Rating {
-    pub fn new(stats: MissionStats, sa_rating: bool) -> Self {
-        Rating { stats, sa_rating }
+    pub fn new(stats: MissionStats, sa_risk: SaRisk) -> Self {
+        Rating {
+            stats,
+            sa_rating: sa_risk != SaRisk::Lost,
+            sa_risk,
+        }
     }
 }
-#[derive(Copy, Clone, Debug)]
+
+/// Graded Silent Assassin status of the current mission
+/// `AtRisk` means that SA is still valid, but a single additional action can lose it
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SaRisk {
+    Safe,
+    AtRisk,
+    Lost,
+}
+#[derive(Copy, Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub struct MissionStats {
     pub shots_fired: u32,
     pub close_encounters: u32,
@@ -95,14 +163,41 @@ impl MissionStats {
             innocents_harmed: arr[7],
         }
     }
+
+    pub fn to_array(&self) -> [u32; 8] {
+        [
+            self.shots_fired,
+            self.close_encounters,
+            self.headshots,
+            self.alerts,
+            self.enemies_killed,
+            self.enemies_harmed,
+            self.innocents_killed,
+            self.innocents_harmed,
+        ]
+    }
 }
 
-// Game Window Position
+/// Display names of the stats, in the same order as `MissionStats::to_array`
+pub const STAT_NAMES: [&str; 8] = [
+    "Shots Fired",
+    "Close Encounters",
+    "Headshots",
+    "Alerts",
+    "Enemies Killed",
+    "Enemies Harmed",
+    "Innocents Killed",
+    "Innocents Harmed",
+];
+
+// Game Window Position and State
 pub struct Window {
     pub left: i32,
     pub top: i32,
     pub right: i32,
     pub bottom: i32,
+    pub minimized: bool,
+    pub focused: bool,
 }
 
 /// Application
@@ -110,10 +205,31 @@ pub mod app {
     // App Components
     pub mod main;
     pub mod overlay;
+    pub mod campaign;
+    pub mod layout;
+    pub mod livesplit;
+    pub mod history;
+    pub mod statistics;
+    pub mod timeline;
+    pub mod hotkeys;
+    pub mod recording;
 
     // App Setup
     pub mod colors;
     pub mod fonts;
+    pub mod i18n;
+    pub mod launch;
+    pub mod logging;
+    pub mod profiles;
+    pub mod settings;
+
+    // Timing
+    pub mod rta;
+    pub mod time_format;
+
+    // Shared test data
+    #[cfg(test)]
+    pub mod test_fixtures;
 
     // System Access {Memory reading + system calls)
     pub mod memory;
@@ -124,5 +240,6 @@ pub mod app {
         pub mod backend_helpers;
         pub mod hm2;
         pub mod hmc;
+        pub mod missions;
     }
 }